@@ -0,0 +1,73 @@
+//! Compares the speed of BitGame with the CellGame playing the same life-like rule, and checks that both
+//! arrive at the same board. Run with `cargo bench`.
+use std::time::{Duration, Instant};
+
+use cell_engine_rs::bit_game::BitGame;
+use cell_engine_rs::cell::{BinaryCell, Cell};
+use cell_engine_rs::game::traits::{CellGame as CellGameTrait, RandCellGame};
+use cell_engine_rs::game::CellGame;
+use cell_engine_rs::game_board::Boundary;
+use cell_engine_rs::life_like::LifeLike;
+
+#[derive(Debug, Clone, Copy, PartialEq, Cell)]
+enum CellConway {
+    #[color("#000000")]
+    Dead,
+    #[color("#FFFFFF")]
+    Alive,
+}
+
+impl BinaryCell for CellConway {
+    fn dead() -> Self {
+        Self::Dead
+    }
+    fn alive() -> Self {
+        Self::Alive
+    }
+}
+
+const GENERATIONS: usize = 500;
+// The boards are compared this often, outside of the measured time
+const COMPARE_EVERY: usize = 50;
+
+/// Step the game the given number of generations and return how long it took
+fn time_steps(game: &mut impl CellGameTrait, generations: usize) -> Duration {
+    let begin = Instant::now();
+    for _ in 0..generations {
+        game.step();
+    }
+    begin.elapsed()
+}
+
+fn main() {
+    for (width, height) in [(256, 256), (1000, 1000)] {
+        let mut dense = CellGame::<CellConway, LifeLike<CellConway>>::new_rand_seeded(
+            width,
+            height,
+            Boundary::Toroidal,
+            42,
+        );
+        let mut bits =
+            BitGame::<CellConway>::new_rand_seeded(width, height, Boundary::Toroidal, 42);
+        let (mut dense_time, mut bits_time) = (Duration::ZERO, Duration::ZERO);
+        for generation in (0..GENERATIONS).step_by(COMPARE_EVERY) {
+            dense_time += time_steps(&mut dense, COMPARE_EVERY);
+            bits_time += time_steps(&mut bits, COMPARE_EVERY);
+            bits.update_board();
+            assert!(
+                dense.get_board().iter().eq(bits.get_board().iter()),
+                "The boards differ after generation {}",
+                generation + COMPARE_EVERY
+            );
+        }
+        println!(
+            "{}x{}, {} generations: CellGame {:?}, BitGame {:?}, {:.1}x faster",
+            width,
+            height,
+            GENERATIONS,
+            dense_time,
+            bits_time,
+            dense_time.as_secs_f64() / bits_time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Derive `Cell`, `FiniteCell` and `RandomCell` for an enum whose variants have no fields.
+///
+/// The states are indexed in the order of the variants, `next` cycles through them in this order
+/// and random cells are drawn uniformly from all states. The color of each variant is given with
+/// `#[color("#RRGGBB")]` or `#[color("#RRGGBBAA")]`. Variants without a color are shades of gray,
+/// from black for the first variant to white for the last one.
+#[proc_macro_derive(Cell, attributes(color))]
+pub fn derive_cell(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream, Error> {
+    let name = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(data) if !data.variants.is_empty() => &data.variants,
+        _ => {
+            return Err(Error::new_spanned(
+                &input,
+                "Cell can only be derived for enums with at least one variant",
+            ))
+        }
+    };
+    let count = variants.len();
+    let mut idents = Vec::new();
+    let mut colors = Vec::new();
+    for (i, variant) in variants.iter().enumerate() {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "Cell can only be derived for enums whose variants have no fields",
+            ));
+        }
+        let color = match variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("color"))
+        {
+            Some(attr) => parse_color(&attr.parse_args::<LitStr>()?)?,
+            None => {
+                let gray = if count > 1 {
+                    (i * 0xFF / (count - 1)) as u8
+                } else {
+                    0
+                };
+                [gray, gray, gray, 0xFF]
+            }
+        };
+        idents.push(&variant.ident);
+        colors.push(quote!([#(#color),*]));
+    }
+    let indices = 0..count;
+    let next = idents.iter().cycle().skip(1);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let krate = quote!(::cell_engine_rs);
+    Ok(quote! {
+        impl #impl_generics #krate::cell::Cell for #name #ty_generics #where_clause {
+            fn to_rgba(&self) -> #krate::rgba::RGBA {
+                match *self {
+                    #(Self::#idents => #krate::rgba::RGBA(#colors),)*
+                }
+            }
+            fn next(&self) -> Self {
+                match *self {
+                    #(Self::#idents => Self::#next,)*
+                }
+            }
+        }
+        impl #impl_generics #krate::cell::FiniteCell for #name #ty_generics #where_clause {
+            const STATE_COUNT: usize = #count;
+            fn state_index(&self) -> usize {
+                match *self {
+                    #(Self::#idents => #indices,)*
+                }
+            }
+            fn from_state_index(index: usize) -> Self {
+                const STATES: [#name; #count] = [#(#name::#idents),*];
+                STATES[index]
+            }
+        }
+        impl #impl_generics #krate::cell::RandomCell for #name #ty_generics #where_clause {
+            fn new_rand_with<R: #krate::__private::rand::Rng + ?Sized>(rng: &mut R) -> Self {
+                let index = #krate::__private::rand::Rng::gen_range(rng, 0..#count);
+                <Self as #krate::cell::FiniteCell>::from_state_index(index)
+            }
+        }
+    })
+}
+
+/// Parse a color given as `#RRGGBB` or `#RRGGBBAA`
+fn parse_color(literal: &LitStr) -> Result<[u8; 4], Error> {
+    let value = literal.value();
+    let error = || {
+        Error::new_spanned(
+            literal,
+            "expected a color like \"#CBCB5C\" or \"#CBCB5CFF\"",
+        )
+    };
+    let hex = value.strip_prefix('#').ok_or_else(error)?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(error());
+    }
+    let mut color = [0xFF; 4];
+    for (channel, i) in color.iter_mut().zip((0..hex.len()).step_by(2)) {
+        *channel = u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| error())?;
+    }
+    Ok(color)
+}
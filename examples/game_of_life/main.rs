@@ -3,14 +3,43 @@ mod game_of_life;
 use cell::CellConway;
 use cell_engine_rs::default_game_runner::GameRunner;
 use cell_engine_rs::game::traits::*;
+use cell_engine_rs::game_board::{Boundary, GameBoard};
+use cell_engine_rs::pattern::{life106, macrocell, plaintext, rle, Pattern, PatternError};
 use game_of_life::ConwaysGame;
 
 fn main() {
     let cell_size = 8;
     let width = 2550 / cell_size;
     let height = 1440 / cell_size;
-    let border_cell = cell::CellConway::Dead;
-    let game = ConwaysGame::new_rand(width, height, border_cell);
+    // Pass the seed printed by an earlier run to replay it, or the path of a pattern file to start with its pattern
+    let mut game = match std::env::args().nth(1) {
+        Some(path) if path.parse::<u64>().is_err() => {
+            let pattern = load_pattern(&path, width, height)
+                .unwrap_or_else(|e| panic!("Cannot load the pattern: {}", e));
+            let (pattern_width, pattern_height) = pattern.dim();
+            let mut board =
+                GameBoard::with_boundary(width, height, CellConway::Dead, Boundary::Toroidal);
+            pattern
+                .stamp(
+                    &mut board,
+                    (width as isize - pattern_width as isize) / 2,
+                    (height as isize - pattern_height as isize) / 2,
+                )
+                .unwrap_or_else(|e| panic!("Cannot place the pattern: {}", e));
+            let mut game = ConwaysGame::from_board(board);
+            if let Some(rule) = &pattern.rule {
+                game.set_rule_str(rule)
+                    .unwrap_or_else(|e| panic!("Cannot play the rule {}: {}", rule, e));
+            }
+            game
+        }
+        seed => {
+            let seed = seed.map_or_else(rand::random, |seed| seed.parse().unwrap());
+            println!("Seed: {}", seed);
+            ConwaysGame::new_rand_seeded(width, height, Boundary::Toroidal, seed)
+        }
+    };
+    game.set_parallel(true);
     let overwrite_decaying = |c: &CellConway| match *c {
         CellConway::Alive => true,
         CellConway::Dead => false,
@@ -18,3 +47,20 @@ fn main() {
     let game_runner = GameRunner::<ConwaysGame>::new(overwrite_decaying);
     game_runner.run(game, "Game of Life");
 }
+
+/// Load the pattern in the format given by the file extension, which is RLE by default.
+/// Macrocell patterns can be far larger than the board, so only the window of the given size around their center is kept.
+fn load_pattern(path: &str, width: usize, height: usize) -> Result<Pattern, PatternError> {
+    match path.rsplit('.').next() {
+        Some("mc") => {
+            let game = macrocell::load::<CellConway>(path, width, height)?;
+            let window = game.to_board(-(width as i64) / 2, -(height as i64) / 2, width, height);
+            let mut pattern = Pattern::from_board(&window, 0, 0, width, height);
+            pattern.rule = Some(game.rule().to_string());
+            Ok(pattern)
+        }
+        Some("cells") => plaintext::load(path),
+        Some("lif" | "life") => life106::load(path),
+        _ => rle::load(path),
+    }
+}
@@ -0,0 +1,31 @@
+use cell_engine_rs::{
+    default_game_runner::GameRunner,
+    game::CellGame,
+    game_board::Boundary,
+    generations::{GenerationsCell, GenerationsRule},
+    soup::Soup,
+};
+
+fn main() {
+    let cell_size = 4;
+    let width = 2550 / cell_size;
+    let height = 1440 / cell_size;
+    // Pass a rule string like 345/2/4 to play another rule than Brian's Brain
+    let rule: GenerationsRule = std::env::args()
+        .nth(1)
+        .map(|rule| rule.parse().expect("The rule has to look like 345/2/4!"))
+        .unwrap_or_default();
+    let seed = rand::random();
+    println!("Rule: {}, seed: {}", rule, seed);
+    let board = Soup::new(&[(rule.dead(), 0.8), (rule.alive(), 0.2)]).generate_seeded(
+        width,
+        height,
+        Boundary::Toroidal,
+        seed,
+    );
+    let mut game = CellGame::with_rule(board, rule);
+    game.set_parallel(true);
+    let overwrite_decaying = |_: &GenerationsCell| true;
+    let game_runner = GameRunner::new(overwrite_decaying);
+    game_runner.run(game, "Generations");
+}
@@ -0,0 +1,77 @@
+use cell_engine_rs::{
+    cell::{Cell, RandomCell},
+    default_game_runner::GameRunner,
+    game::{traits::RandCellGame, CellGame},
+    game_board::{Boundary, Neighborhood, NeighborhoodShape},
+    game_rules::GameRule,
+    rgba::RGBA,
+};
+use rand::Rng;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum HexCell {
+    Dead,
+    Alive,
+}
+
+impl Cell for HexCell {
+    fn to_rgba(&self) -> RGBA {
+        match *self {
+            Self::Dead => RGBA::black(),
+            Self::Alive => RGBA([0x5C, 0xCB, 0xCB, 0xFF]),
+        }
+    }
+    fn next(&self) -> Self {
+        match *self {
+            Self::Dead => Self::Alive,
+            Self::Alive => Self::Dead,
+        }
+    }
+}
+
+impl RandomCell for HexCell {
+    fn new_rand_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        if rng.gen::<f32>() < 0.3 {
+            Self::Alive
+        } else {
+            Self::Dead
+        }
+    }
+}
+
+/// Hexagonal Life with the rule B2/S34
+#[derive(Default, Clone, Copy)]
+struct HexLifeRule {}
+
+impl GameRule for HexLifeRule {
+    type Cell = HexCell;
+
+    fn apply(cell: &Self::Cell, neighbors: Neighborhood<Self::Cell>) -> Self::Cell {
+        let sum_alive = neighbors.count(&HexCell::Alive);
+        match (*cell, sum_alive) {
+            (HexCell::Dead, 2) => HexCell::Alive,
+            (HexCell::Alive, 3..=4) => HexCell::Alive,
+            _ => HexCell::Dead,
+        }
+    }
+    fn neighborhood() -> NeighborhoodShape {
+        NeighborhoodShape::Hexagonal
+    }
+}
+
+type HexLifeGame = CellGame<HexCell, HexLifeRule>;
+
+fn main() {
+    let width = 1280 / 8;
+    let height = 1440 / 8;
+    // Pass the seed printed by an earlier run to replay it
+    let seed = std::env::args()
+        .nth(1)
+        .map(|seed| seed.parse().expect("The seed has to be a number!"))
+        .unwrap_or_else(rand::random);
+    println!("Seed: {}", seed);
+    let game = HexLifeGame::new_rand_seeded(width, height, Boundary::Toroidal, seed);
+    let overwrite_decaying = |c: &HexCell| *c == HexCell::Alive;
+    let game_runner = GameRunner::new(overwrite_decaying);
+    game_runner.run(game, "Hexagonal Life");
+}
@@ -2,6 +2,7 @@ use cell_engine_rs::{
     cell::{Cell, RandomCell},
     default_game_runner::GameRunner,
     game::{traits::CellGame as CellGameTrait, CellGame},
+    game_board::{Direction, Neighborhood},
     game_rules::GameRule,
     rgba::RGBA,
 };
@@ -55,8 +56,8 @@ impl Cell for LangtonsCell {
 }
 
 impl RandomCell for LangtonsCell {
-    fn new_rand() -> Self {
-        match rand::thread_rng().gen_range(0..=1) {
+    fn new_rand_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        match rng.gen_range(0..=1) {
             0 => Self::Black(None),
             1 => Self::White(None),
             _ => unreachable!(),
@@ -78,50 +79,45 @@ struct LangtonsRule {}
 impl GameRule for LangtonsRule {
     type Cell = LangtonsCell;
 
-    fn apply(
-        cell: &Self::Cell,
-        neighbor_iter: cell_engine_rs::game_board::iter::NeighborhoodIterator<Self::Cell>,
-    ) -> Self::Cell {
+    fn apply(cell: &Self::Cell, neighbors: Neighborhood<Self::Cell>) -> Self::Cell {
         match *cell {
             White(Some(_)) => return Black(None),
             Black(Some(_)) => return White(None),
             _ => (),
         }
-        let mut ant = None;
-
-        // Rule depends on order of iteration of neighbor iterator
-        // Assuming iteration starts at top left
-        'label: for (i, &neighbor) in neighbor_iter.enumerate() {
-            match (i, neighbor) {
-                (1, neighbor)
-                    if neighbor == White(Some(Ant::East)) || neighbor == Black(Some(Ant::West)) =>
-                {
-                    ant = Some(Ant::South);
-                    break 'label;
-                }
-                (3, neighbor)
-                    if neighbor == White(Some(Ant::South))
-                        || neighbor == Black(Some(Ant::North)) =>
-                {
-                    ant = Some(Ant::West);
-                    break 'label;
-                }
-                (4, neighbor)
-                    if neighbor == White(Some(Ant::North))
-                        || neighbor == Black(Some(Ant::South)) =>
-                {
-                    ant = Some(Ant::East);
-                    break 'label;
-                }
-                (6, neighbor)
-                    if neighbor == White(Some(Ant::West)) || neighbor == Black(Some(Ant::East)) =>
-                {
-                    ant = Some(Ant::North);
-                    break 'label;
-                }
-                _ => (),
-            }
-        }
+        // An ant on a neighbor moves onto this cell if it faces this cell after turning
+        let arrivals = [
+            (
+                Direction::N,
+                White(Some(Ant::East)),
+                Black(Some(Ant::West)),
+                Ant::South,
+            ),
+            (
+                Direction::W,
+                White(Some(Ant::South)),
+                Black(Some(Ant::North)),
+                Ant::West,
+            ),
+            (
+                Direction::E,
+                White(Some(Ant::North)),
+                Black(Some(Ant::South)),
+                Ant::East,
+            ),
+            (
+                Direction::S,
+                White(Some(Ant::West)),
+                Black(Some(Ant::East)),
+                Ant::North,
+            ),
+        ];
+        let ant = arrivals
+            .iter()
+            .find(|&&(direction, on_white, on_black, _)| {
+                neighbors[direction] == on_white || neighbors[direction] == on_black
+            })
+            .map(|&(.., ant)| ant);
         match *cell {
             White(_) => White(ant),
             Black(_) => Black(ant),
@@ -0,0 +1,27 @@
+use cell_engine_rs::{
+    default_game_runner::GameRunner, game::CellGame, game_board::Boundary, rule_table::RuleTable,
+    soup::Soup,
+};
+
+fn main() {
+    let cell_size = 8;
+    let width = 2550 / cell_size;
+    let height = 1440 / cell_size;
+    // Pass the path of a .table or .rule file to play another rule than WireWorld
+    let table = match std::env::args().nth(1) {
+        Some(path) => RuleTable::load(&path),
+        None => RuleTable::parse(include_str!("WireWorld.rule")),
+    }
+    .unwrap_or_else(|e| panic!("Cannot load the rule table: {}", e));
+    println!("Rule: {} with {} states", table.name(), table.states());
+    // Mostly empty cells, and every other state equally often
+    let states: Vec<_> = (0..table.states())
+        .map(|state| (table.cell(state as u8), if state == 0 { 4.0 } else { 1.0 }))
+        .collect();
+    let board =
+        Soup::new(&states).generate(width, height, Boundary::Toroidal, &mut rand::thread_rng());
+    let mut game = CellGame::with_rule(board, table);
+    game.set_parallel(true);
+    let game_runner = GameRunner::new(|_| true);
+    game_runner.run(game, "Rule Table");
+}
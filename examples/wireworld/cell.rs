@@ -1,30 +1,13 @@
 pub use cell_engine_rs::cell::*;
-use cell_engine_rs::rgba::RGBA;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Cell)]
 pub enum WireCell {
+    #[color("#000000")]
     Off,
+    #[color("#CBCB5C")]
     Wire,
+    #[color("#C52929")]
     ElectronHead,
+    #[color("#2929C5")]
     ElectronTail,
 }
-
-use WireCell::*;
-impl Cell for WireCell {
-    fn to_rgba(&self) -> RGBA {
-        match *self {
-            Self::Off => RGBA::black(),
-            Self::Wire => RGBA([0xCB, 0xCB, 0x5C, 0xFF]),
-            Self::ElectronHead => RGBA([0xC5, 0x29, 0x29, 0xff]),
-            Self::ElectronTail => RGBA([0x29, 0x29, 0xC5, 0xff]),
-        }
-    }
-    fn next(&self) -> Self {
-        match *self {
-            Off => Wire,
-            Wire => ElectronHead,
-            ElectronHead => ElectronTail,
-            ElectronTail => Off,
-        }
-    }
-}
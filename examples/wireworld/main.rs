@@ -2,6 +2,8 @@ mod cell;
 mod wireworld;
 use cell::WireCell;
 use cell_engine_rs::default_game_runner::GameRunner;
+use cell_engine_rs::game_board::Boundary;
+use cell_engine_rs::image;
 
 use wireworld::*;
 
@@ -10,7 +12,17 @@ fn main() {
     let width = 2550 / cell_size;
     let height = 1440 / cell_size;
     let border_cell = cell::WireCell::Off;
-    let game = wireworld::WireGame::new(width, height, border_cell);
+    // Pass the path of a PNG drawn in the colors of the cells to start with that circuit
+    let mut game = match std::env::args().nth(1) {
+        Some(path) => {
+            let board = image::load_png(path, &image::palette(), Boundary::Fixed(border_cell))
+                .unwrap_or_else(|e| panic!("Cannot load the circuit: {}", e));
+            wireworld::WireGame::from_board(board)
+        }
+        None => wireworld::WireGame::new(width, height, border_cell),
+    };
+    // Most of a circuit never changes, so only the tiles around the electrons are recomputed
+    game.set_activity_tracking(Some(16));
     let overwrite_decaying =
         |c: &WireCell| matches!(*c, WireCell::ElectronHead | WireCell::ElectronTail);
     let game_runner = GameRunner::new(overwrite_decaying);
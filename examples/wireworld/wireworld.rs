@@ -1,6 +1,8 @@
 use crate::cell::WireCell;
 pub use cell_engine_rs::game::traits::CellGame as CellGameTrait;
-use cell_engine_rs::{game::CellGame, game_board::iter::*, game_rules::*};
+use cell_engine_rs::{
+    compiled_rule::CompiledRule, game::CellGame, game_board::Neighborhood, game_rules::*,
+};
 
 #[derive(Default, Clone, Copy)]
 pub struct WireworldRule {}
@@ -8,13 +10,11 @@ pub struct WireworldRule {}
 impl GameRule for WireworldRule {
     type Cell = WireCell;
 
-    fn apply(cell: &Self::Cell, neighbor_iter: NeighborhoodIterator<Self::Cell>) -> Self::Cell {
+    fn apply(cell: &Self::Cell, neighbors: Neighborhood<Self::Cell>) -> Self::Cell {
         match *cell {
             WireCell::Off => WireCell::Off,
             WireCell::Wire => {
-                let sum = neighbor_iter
-                    .filter(|&cell| *cell == WireCell::ElectronHead)
-                    .count();
+                let sum = neighbors.count(&WireCell::ElectronHead);
                 if (1..=2).contains(&sum) {
                     WireCell::ElectronHead
                 } else {
@@ -27,4 +27,5 @@ impl GameRule for WireworldRule {
     }
 }
 
-pub type WireGame = CellGame<WireCell, WireworldRule>;
+// Wireworld has few enough states to look up the next state of every neighborhood in a table
+pub type WireGame = CellGame<WireCell, CompiledRule<WireworldRule>>;
@@ -1,14 +1,50 @@
 use crate::rgba::RGBA;
+use rand::Rng;
 
-pub trait Cell: Clone + Copy + PartialEq {
+pub use cell_engine_derive::Cell;
+
+pub trait Cell: Clone + Copy + PartialEq + Send + Sync {
     fn to_rgba(&self) -> RGBA;
     fn next(&self) -> Self;
 }
 
 pub trait RandomCell: Cell {
-    fn new_rand() -> Self;
+    /// Produce a random cell, drawing from the given random number generator
+    fn new_rand_with<R: Rng + ?Sized>(rng: &mut R) -> Self;
+    /// Produce a random cell, drawing from the thread-local random number generator
+    fn new_rand() -> Self {
+        Self::new_rand_with(&mut rand::thread_rng())
+    }
+}
+
+/// The random number generator behind seeded initialization.
+/// Its output does not depend on the platform, so a seed always reproduces the same cells.
+pub type SeedRng = rand_chacha::ChaCha8Rng;
+
+/// A cell with exactly two states, such as the cells of life-like automata.
+pub trait BinaryCell: Cell {
+    fn dead() -> Self;
+    fn alive() -> Self;
+    fn is_alive(&self) -> bool {
+        *self == Self::alive()
+    }
+}
+
+/// A cell with a small, finite set of states, each of which has an index from 0 to `STATE_COUNT - 1`.
+pub trait FiniteCell: Cell {
+    /// The number of states
+    const STATE_COUNT: usize;
+    /// Get the index of the state of the cell
+    fn state_index(&self) -> usize;
+    /// Construct the cell in the state with the given index. Panics if the index is not below `STATE_COUNT`.
+    fn from_state_index(index: usize) -> Self;
+    /// Get every state in the order of their indices
+    fn states() -> Vec<Self> {
+        (0..Self::STATE_COUNT).map(Self::from_state_index).collect()
+    }
 }
 
+#[cfg(test)]
 pub(crate) mod mock {
     pub use super::*;
     use rand::{
@@ -16,6 +52,7 @@ pub(crate) mod mock {
         Rng,
     };
     #[derive(Debug, Clone, Copy, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum CellConway {
         Dead,
         Alive,
@@ -31,8 +68,27 @@ pub(crate) mod mock {
     }
 
     impl RandomCell for CellConway {
-        fn new_rand() -> Self {
-            rand::random()
+        fn new_rand_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+            rng.gen()
+        }
+    }
+
+    impl BinaryCell for CellConway {
+        fn dead() -> Self {
+            Self::Dead
+        }
+        fn alive() -> Self {
+            Self::Alive
+        }
+    }
+
+    impl FiniteCell for CellConway {
+        const STATE_COUNT: usize = 2;
+        fn state_index(&self) -> usize {
+            *self as usize
+        }
+        fn from_state_index(index: usize) -> Self {
+            [Self::Dead, Self::Alive][index]
         }
     }
 
@@ -50,4 +106,52 @@ pub(crate) mod mock {
             }
         }
     }
+
+    /// Conway's Game of Life as a plain GameRule
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct ConwayRule {}
+
+    impl crate::game_rules::GameRule for ConwayRule {
+        type Cell = CellConway;
+        fn apply(
+            cell: &CellConway,
+            neighbors: crate::game_board::Neighborhood<CellConway>,
+        ) -> CellConway {
+            let alive = neighbors.count(&CellConway::Alive);
+            match (*cell, alive) {
+                (CellConway::Alive, 2) | (_, 3) => CellConway::Alive,
+                _ => CellConway::Dead,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Cell)]
+    enum Derived {
+        #[color("#CBCB5C")]
+        Wire,
+        Off,
+        #[color("#2929C580")]
+        Tail,
+    }
+
+    #[test]
+    fn test_derive_cell() {
+        assert_eq!(Derived::STATE_COUNT, 3);
+        assert_eq!(Derived::states(), [Derived::Wire, Derived::Off, Derived::Tail]);
+        assert_eq!(Derived::Tail.state_index(), 2);
+        assert_eq!(Derived::from_state_index(1), Derived::Off);
+        assert_eq!(Derived::Tail.next(), Derived::Wire);
+        assert_eq!(Derived::Wire.to_rgba().get_raw(), [0xCB, 0xCB, 0x5C, 0xFF]);
+        assert_eq!(Derived::Off.to_rgba().get_raw(), [0x7F, 0x7F, 0x7F, 0xFF]);
+        assert_eq!(Derived::Tail.to_rgba().get_raw(), [0x29, 0x29, 0xC5, 0x80]);
+        let mut rng = SeedRng::seed_from_u64(0);
+        let cells: Vec<Derived> = (0..100).map(|_| Derived::new_rand_with(&mut rng)).collect();
+        assert!(Derived::states().iter().all(|state| cells.contains(state)));
+    }
 }
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use crate::cell::*;
+use crate::game_board::*;
+use crate::game_rules::*;
+
+/// The most entries the transition table of a CompiledRule may have.
+/// Rules whose table would be larger are applied directly instead.
+pub const MAX_TABLE_SIZE: usize = 1 << 22;
+
+/// A deterministic rule over cells with finitely many states, evaluated once for every possible neighborhood
+/// and afterwards applied by looking up the result in a transition table.
+///
+/// The table is indexed by the states of the 3x3 square around a cell, so it has `STATE_COUNT^9` entries.
+/// If this exceeds `MAX_TABLE_SIZE`, the neighborhood reaches further than the adjacent cells,
+/// or the rule is not deterministic, the rule is applied directly.
+/// Either way the results are identical to those of the wrapped rule.
+#[derive(Clone)]
+pub struct CompiledRule<R: ContextGameRule>
+where
+    R::Cell: FiniteCell,
+{
+    rule: R,
+    table: Option<Arc<Vec<R::Cell>>>,
+}
+
+impl<R: ContextGameRule> CompiledRule<R>
+where
+    R::Cell: FiniteCell,
+{
+    /// Compile the rule into a transition table, if the table is small enough
+    pub fn new(rule: R) -> Self {
+        let size = R::Cell::STATE_COUNT
+            .checked_pow(9)
+            .filter(|&size| size <= MAX_TABLE_SIZE);
+        let table = match size {
+            Some(size) if rule.neighborhood().radius() == 1 && rule.is_deterministic() => {
+                Some(Arc::new(Self::compile(&rule, size)))
+            }
+            _ => None,
+        };
+        Self { rule, table }
+    }
+    /// Get the wrapped rule
+    pub fn rule(&self) -> &R {
+        &self.rule
+    }
+    /// Whether the rule is applied through a transition table, rather than directly
+    pub fn is_compiled(&self) -> bool {
+        self.table.is_some()
+    }
+    fn compile(rule: &R, size: usize) -> Vec<R::Cell> {
+        let states = R::Cell::states();
+        let n = states.len();
+        // A single cell whose border holds the rest of the square around it
+        let mut board = GameBoard::new(1, 1, states[0]);
+        board.set_neighborhood(rule.neighborhood());
+        let mut context = RuleContext::new(0, 0, 0, 0);
+        let mut table = Vec::with_capacity(size);
+        for index in 0..size {
+            // The top left cell of the square is the most significant digit of the index
+            board.fill_outer(|x, y| {
+                let position = ((y + 1) * 3 + x + 1) as u32;
+                states[index / n.pow(8 - position) % n]
+            });
+            let cell = *board.get(0, 0).unwrap();
+            table.push(rule.apply_in_context(&cell, board.neighbors(0, 0), &mut context));
+        }
+        table
+    }
+}
+
+impl<R: ContextGameRule> Default for CompiledRule<R>
+where
+    R::Cell: FiniteCell,
+{
+    fn default() -> Self {
+        Self::new(R::default())
+    }
+}
+
+impl<R: ContextGameRule> ContextGameRule for CompiledRule<R>
+where
+    R::Cell: FiniteCell,
+{
+    type Cell = R::Cell;
+    fn apply_in_context(
+        &self,
+        cell: &Self::Cell,
+        neighbors: Neighborhood<Self::Cell>,
+        context: &mut RuleContext,
+    ) -> Self::Cell {
+        match &self.table {
+            Some(table) => {
+                let n = R::Cell::STATE_COUNT;
+                // The indices of the three rows are computed independently of each other, which is a lot faster
+                // than adding up the cells one after the other
+                let index = neighbors.square_rows().fold(0, |index, row| {
+                    let row_index = (row[0].state_index() * n + row[1].state_index()) * n
+                        + row[2].state_index();
+                    index * n * n * n + row_index
+                });
+                table[index]
+            }
+            None => self.rule.apply_in_context(cell, neighbors, context),
+        }
+    }
+    fn neighborhood(&self) -> NeighborhoodShape {
+        self.rule.neighborhood()
+    }
+    fn is_deterministic(&self) -> bool {
+        self.rule.is_deterministic()
+    }
+    fn parse_rule(rule: &str) -> Result<Self, ParseRuleError> {
+        R::parse_rule(rule).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+    use crate::game::{traits::CellGame as CellGameTrait, CellGame};
+
+    #[test]
+    fn test_compiled_rule_matches_rule() {
+        let board = GameBoard::new_rand_seeded(64, 48, Boundary::Toroidal, 5);
+        let mut game: CellGame<CellConway, ConwayRule> = CellGame::from_board(board.clone());
+        let mut compiled: CellGame<CellConway, CompiledRule<ConwayRule>> =
+            CellGame::from_board(board);
+        assert!(compiled.rule().is_compiled());
+        for _ in 0..20 {
+            game.step();
+            compiled.step();
+        }
+        assert!(game.get_board().iter().eq(compiled.get_board().iter()));
+    }
+}
@@ -1,6 +1,13 @@
-use crate::{dprintln, game::traits::CellGame as CellGameTrait, visuals::Visuals};
+use crate::{
+    cell::Cell,
+    dprintln,
+    game::traits::CellGame as CellGameTrait,
+    image::recorder::{Recorder, RecordingFormat},
+    visuals::Visuals,
+};
 // use log::trace;
-use std::time::{Duration, Instant};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use winit::{
     dpi::PhysicalPosition,
     event::{
@@ -10,9 +17,6 @@ use winit::{
     event_loop::{ControlFlow, EventLoop, EventLoopProxy},
     window::{Fullscreen, WindowBuilder},
 };
-mod traits {
-    pub trait UserEvent: PartialEq + Clone + Copy {}
-}
 pub struct MouseState {
     pub position: PhysicalPosition<f64>,
     pub left: ElementState,
@@ -47,11 +51,22 @@ pub struct MouseInput {
     pub state: ElementState,
     pub button: MouseButton,
 }
-pub struct GameContext {
+/// Pixels per cell in recordings, which are too small to watch at one pixel per cell
+const RECORDING_SCALE: usize = 4;
+/// Frames a recording keeps in memory, after which it is saved and stops
+const MAX_RECORDING_FRAMES: usize = 1000;
+
+pub struct GameContext<C: Cell> {
     last_cell_stepped: Option<(usize, usize)>,
     update_time: Duration,
     paused: bool,
     stop: bool,
+    overwrite_decaying: fn(&C) -> bool,
+    recorder: Option<Recorder<C>>,
+    // Steps taken since the recorder last saw the board
+    unrecorded_steps: usize,
+    // Recordings being encoded in the background, which are waited for before exiting
+    saving: Vec<JoinHandle<()>>,
 }
 
 pub struct GameRunner<CG: 'static + CellGameTrait> {
@@ -78,18 +93,24 @@ impl<CG: CellGameTrait> GameRunner<CG> {
             .build(&self.event_loop)
             .expect("Could not construct fullscreen window!");
         //let dpi_scaling = window.scale_factor();
-        let mut visuals = Visuals::new(game_dim.0, game_dim.1, window);
+        let topology = game.get_board().topology();
+        let mut visuals = Visuals::with_topology(game_dim.0, game_dim.1, topology, window);
         let decay_decider = self.overwrite_decaying;
         let mut game_context = GameContext {
             last_cell_stepped: None,
             update_time: Duration::from_secs_f32(1. / 4.),
             paused: false,
             stop: false,
+            overwrite_decaying: decay_decider,
+            recorder: None,
+            unrecorded_steps: 0,
+            saving: Vec::new(),
         };
         let mut mouse_state = MouseState::default();
         let mut modifier_state = ModifiersState::default();
         let event_loop_proxy = self.event_loop.create_proxy();
         let mut last_game_update = Instant::now();
+        spawn_rule_reader(self.event_loop.create_proxy());
         visuals.get_window().set_visible(true);
         self.event_loop.run(move |event, _, control_flow| {
             *control_flow = ControlFlow::Poll;
@@ -155,41 +176,87 @@ impl<CG: CellGameTrait> GameRunner<CG> {
                         let update_delay = begin - last_game_update;
                         if update_delay >= game_context.update_time {
                             game.step();
+                            game_context.unrecorded_steps += 1;
                             last_game_update = Instant::now();
                             visuals.get_window().request_redraw();
                         }
                     }
                 }
                 Event::RedrawRequested(_) => {
+                    game.update_board();
                     visuals.update_pixel_buffer(&game, decay_decider);
+                    if let Some(recorder) = &mut game_context.recorder {
+                        recorder.set_decay_multiplier(visuals.get_decay_multiplier());
+                        recorder.advance(&game, game_context.unrecorded_steps);
+                        if recorder.is_full() {
+                            println!("The recording reached {} frames", MAX_RECORDING_FRAMES);
+                            toggle_recording(
+                                &game,
+                                &mut game_context,
+                                visuals.get_decay_multiplier(),
+                            );
+                        }
+                    }
+                    game_context.unrecorded_steps = 0;
                     if visuals.render().is_err() {
                         eprintln!("Error: Could not render to pixel buffer!");
                         *control_flow = ControlFlow::Exit;
                     }
                 }
+                Event::LoopDestroyed => {
+                    for saving in game_context.saving.drain(..) {
+                        let _ = saving.join();
+                    }
+                }
                 _ => (),
             }
         });
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone)]
 enum UserEvent {
     StepCell { x: usize, y: usize },
+    SetRule(String),
 }
-impl traits::UserEvent for UserEvent {}
 
-fn on_mouse_state_updated(
+/// Read rule strings like `B36/S23` from the standard input in the background,
+/// one per line, and hand them to the event loop to switch the rule of the running game.
+fn spawn_rule_reader(event_loop_proxy: EventLoopProxy<UserEvent>) {
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            let rule = match line {
+                Ok(rule) if !rule.trim().is_empty() => rule.trim().to_string(),
+                Ok(_) => continue,
+                Err(_) => break,
+            };
+            // The event loop is gone once the window is closed
+            if event_loop_proxy
+                .send_event(UserEvent::SetRule(rule))
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+}
+
+fn on_mouse_state_updated<C: Cell>(
     mouse_state: &MouseState,
     _modifier_state: &ModifiersState,
     visuals: &mut Visuals,
-    game_context: &mut GameContext,
+    game_context: &mut GameContext<C>,
     event_loop_proxy: &EventLoopProxy<UserEvent>,
 ) {
     let (x, y) = visuals
         .get_buffer()
         .window_pos_to_pixel(mouse_state.position.into())
         .unwrap_or_else(|pos| visuals.get_buffer().clamp_pixel_pos(pos));
+    // Hexagonal boards leave parts of the pixel buffer empty
+    let (x, y) = match visuals.pixel_to_cell(x, y) {
+        Some(cell) => cell,
+        None => return,
+    };
     if let ElementState::Pressed = mouse_state.left {
         dprintln!("Clicked at {}, {}", x, y);
         //trace!("Clicked at {}, {}", x, y);
@@ -201,12 +268,12 @@ fn on_mouse_state_updated(
     }
 }
 
-fn on_keyboard_input<T: CellGameTrait>(
+fn on_keyboard_input<T: 'static + CellGameTrait>(
     input: KeyboardInput,
     modifier_state: &ModifiersState,
     visuals: &mut Visuals,
     game: &mut T,
-    game_context: &mut GameContext,
+    game_context: &mut GameContext<T::Cell>,
     _event_loop_proxy: &EventLoopProxy<UserEvent>,
 ) {
     let KeyboardInput {
@@ -235,6 +302,9 @@ fn on_keyboard_input<T: CellGameTrait>(
                 }
                 _ => {
                     game_context.update_time = game_context.update_time.mul_f32(0.9);
+                    if let Some(recorder) = &mut game_context.recorder {
+                        recorder.set_frame_delay(game_context.update_time);
+                    }
                     println!("Decreased update_time");
                 }
             }
@@ -253,23 +323,70 @@ fn on_keyboard_input<T: CellGameTrait>(
                 }
                 _ => {
                     game_context.update_time = game_context.update_time.mul_f32(1.1);
+                    if let Some(recorder) = &mut game_context.recorder {
+                        recorder.set_frame_delay(game_context.update_time);
+                    }
                     println!("Increased update_time")
                 }
             }
         }
         Some(VirtualKeyCode::Space) => {
             game.step();
+            game_context.unrecorded_steps += 1;
             visuals.get_window().request_redraw();
         }
+        Some(VirtualKeyCode::R) => {
+            toggle_recording(game, game_context, visuals.get_decay_multiplier())
+        }
         Some(VirtualKeyCode::Escape) => game_context.stop = true,
         _ => {}
     }
 }
 
+/// Start recording the game, or stop and save the recording as a GIF in the working directory.
+/// The GIF is encoded on another thread, so the window keeps running meanwhile.
+fn toggle_recording<T: 'static + CellGameTrait>(
+    game: &T,
+    game_context: &mut GameContext<T::Cell>,
+    decay_multiplier: f32,
+) {
+    match game_context.recorder.take() {
+        Some(recorder) => {
+            let time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs());
+            let path = format!("recording-{}.gif", time);
+            println!("Saving the recording to {}", path);
+            game_context.saving.retain(|saving| !saving.is_finished());
+            game_context
+                .saving
+                .push(std::thread::spawn(move || match recorder.save(&path) {
+                    Ok(()) => println!(
+                        "Saved the recording of {} frames to {}",
+                        recorder.frame_count(),
+                        path
+                    ),
+                    Err(e) => eprintln!("Error: Could not save the recording to {}: {}", path, e),
+                }));
+        }
+        None => {
+            let mut recorder = Recorder::new(RecordingFormat::Gif, game_context.overwrite_decaying);
+            recorder.set_scale(RECORDING_SCALE);
+            recorder.set_max_frames(MAX_RECORDING_FRAMES);
+            recorder.set_frame_delay(game_context.update_time);
+            recorder.set_decay_multiplier(decay_multiplier);
+            recorder.capture(game);
+            game_context.recorder = Some(recorder);
+            game_context.unrecorded_steps = 0;
+            println!("Started recording");
+        }
+    }
+}
+
 fn on_user_event<T: CellGameTrait>(
     event: UserEvent,
     game: &mut T,
-    game_context: &mut GameContext,
+    game_context: &mut GameContext<T::Cell>,
     visuals: &mut Visuals,
     _event_loop_proxy: &EventLoopProxy<UserEvent>,
 ) {
@@ -284,5 +401,9 @@ fn on_user_event<T: CellGameTrait>(
             game_context.last_cell_stepped = Some((x, y));
             visuals.get_window().request_redraw();
         }
+        UserEvent::SetRule(rule) => match game.set_rule_str(&rule) {
+            Ok(()) => println!("Switched to rule {}", rule),
+            Err(e) => eprintln!("Error: Could not switch to rule {}: {}", rule, e),
+        },
     }
 }
@@ -4,39 +4,226 @@ use crate::game_rules::*;
 
 pub mod traits {
     use super::*;
-    pub trait CellGame {
+    pub trait CellGame: Sized {
         type Cell: Cell;
-        type GameRule: GameRule;
+        /// The rule the game plays
+        type GameRule: ContextGameRule<Cell = Self::Cell>;
         fn get_board(&self) -> &GameBoard<Self::Cell>;
+        /// Bring the GameBoard returned by `get_board` up to date. Games which do not step on a
+        /// GameBoard directly only write their state into it here, so call this before looking at it.
+        fn update_board(&mut self) {}
+        /// Get the seed the random parts of the game are derived from, such as a random initial state
+        /// or the random decisions of stochastic rules, to replay the game exactly.
+        /// Returns None if nothing about the game is random.
+        fn seed(&self) -> Option<u64> {
+            None
+        }
+        /// Get the tiles of the GameBoard returned by `get_board` whose cells changed with the last call to
+        /// `update_board`. Returns None if the game does not track this, in which case any cell could have changed.
+        fn dirty_tiles(&self) -> Option<&DirtyTiles> {
+            None
+        }
+        /// Replace the rule of the game with the one described by the rule string, such as `B36/S23`.
+        /// Returns an error if the string is malformed or the rule of the game cannot be described by a string.
+        fn set_rule_str(&mut self, rule: &str) -> Result<(), ParseRuleError> {
+            let _ = rule;
+            Err(ParseRuleError::Unsupported)
+        }
         fn step(&mut self);
+        /// Get the number of steps the game took
+        fn generation(&self) -> u64;
+        /// Set every cell to the given cell. The generation is kept, so stochastic rules do not repeat
+        /// the random numbers they drew before.
         fn clear(&mut self, clear_cell: Self::Cell);
         fn dim(&self) -> (usize, usize);
         fn next_cell(&mut self, x: usize, y: usize);
-        fn new(width: usize, height: usize, border_cell: Self::Cell) -> Self;
+        fn new(width: usize, height: usize, border_cell: Self::Cell) -> Self {
+            Self::with_boundary(width, height, border_cell, Boundary::Fixed(border_cell))
+        }
+        /// Construct a game filled with the given cell whose edges behave according to the given boundary.
+        fn with_boundary(
+            width: usize,
+            height: usize,
+            cell: Self::Cell,
+            boundary: Boundary<Self::Cell>,
+        ) -> Self;
     }
     pub trait RandCellGame: CellGame {
-        fn new_rand(width: usize, height: usize, border_cell: Self::Cell) -> Self;
+        fn new_rand(width: usize, height: usize, border_cell: Self::Cell) -> Self {
+            Self::new_rand_with_boundary(width, height, Boundary::Fixed(border_cell))
+        }
+        /// Construct a game with random cells from a random seed, which is recorded by the game.
+        fn new_rand_with_boundary(
+            width: usize,
+            height: usize,
+            boundary: Boundary<Self::Cell>,
+        ) -> Self {
+            Self::new_rand_seeded(width, height, boundary, rand::random())
+        }
+        /// Construct a game with random cells determined by the seed.
+        /// The same seed always produces the same game.
+        fn new_rand_seeded(
+            width: usize,
+            height: usize,
+            boundary: Boundary<Self::Cell>,
+            seed: u64,
+        ) -> Self;
     }
 }
 
-pub struct CellGame<C: Cell, R: GameRule<Cell = C>> {
+pub struct CellGame<C: Cell, R: ContextGameRule<Cell = C>> {
     pub(crate) rule: R,
     pub(crate) board: GameBoard<C>,
     pub(crate) scratch_board: GameBoard<C>,
+    pub(crate) parallel: bool,
+    pub(crate) activity: Option<Activity>,
+    pub(crate) seed: u64,
+    // Whether the seed was chosen for the game or drawn for its initial state, rather than drawn in case a rule needs it
+    pub(crate) seeded: bool,
+    pub(crate) generation: u64,
+}
+
+/// Tracks which tiles of the board changed, so the tiles surrounded by unchanged tiles can be skipped.
+/// This relies on rules always producing the same cell for the same neighborhood.
+#[derive(Debug, Clone)]
+pub(crate) struct Activity {
+    // Changed in the last generation or edited since
+    changed: DirtyTiles,
+    // Changed since the last call to `update_board`
+    pending: DirtyTiles,
+    // Changed between the last two calls to `update_board`
+    rendered: DirtyTiles,
+}
+
+impl Activity {
+    fn new(width: usize, height: usize, tile_size: usize) -> Self {
+        let tiles = DirtyTiles::new(width, height, tile_size);
+        Self {
+            changed: tiles.clone(),
+            pending: tiles.clone(),
+            rendered: tiles,
+        }
+    }
+    fn mark_cell(&mut self, x: usize, y: usize) {
+        self.changed.mark_cell(x, y);
+        self.pending.mark_cell(x, y);
+    }
+    fn mark_all(&mut self) {
+        self.changed.mark_all();
+        self.pending.mark_all();
+    }
 }
 
-impl<C: RandomCell, R: GameRule<Cell = C>> traits::RandCellGame for CellGame<C, R> {
-    fn new_rand(width: usize, height: usize, border_cell: Self::Cell) -> Self {
-        let board = GameBoard::new_rand(width, height, border_cell);
+impl<C: Cell, R: ContextGameRule<Cell = C>> CellGame<C, R> {
+    /// Construct a game starting with the cells and boundary of the GameBoard
+    pub fn from_board(board: GameBoard<C>) -> Self {
+        Self::with_rule(board, R::default())
+    }
+    /// Construct a game of the given rule instance starting with the cells and boundary of the GameBoard
+    pub fn with_rule(mut board: GameBoard<C>, rule: R) -> Self {
+        board.set_neighborhood(rule.neighborhood());
         Self {
-            rule: R::default(),
+            rule,
             board: board.clone(),
             scratch_board: board,
+            parallel: false,
+            activity: None,
+            seed: rand::random(),
+            seeded: false,
+            generation: 0,
         }
     }
+    /// Get the rule of the game
+    pub fn rule(&self) -> &R {
+        &self.rule
+    }
+    /// Replace the rule of the game. The board takes on the neighborhood of the new rule.
+    /// Activity tracking keeps its tile size, unless the new rule is not deterministic, which turns it off
+    /// like `set_activity_tracking(None)`. It is not turned back on by switching to a deterministic rule again.
+    pub fn set_rule(&mut self, rule: R) {
+        self.board.set_neighborhood(rule.neighborhood());
+        self.scratch_board.set_neighborhood(rule.neighborhood());
+        self.rule = rule;
+        let tile_size = self.activity_tracking();
+        self.set_activity_tracking(None);
+        if self.rule.is_deterministic() {
+            self.set_activity_tracking(tile_size);
+        }
+    }
+    /// Select whether `step` applies the rule on all cores, splitting the board into bands of rows.
+    /// Both ways produce identical boards, the parallel one pays off for large boards.
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+    /// Whether `step` applies the rule on all cores
+    pub fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+    /// Divide the board into tiles of the given size and only recompute the tiles in which or next to which
+    /// cells changed in the last generation. The tiles have to be at least as large as the radius of the neighborhood.
+    /// Passing None recomputes every cell in every step, which is faster for boards which change everywhere.
+    pub fn set_activity_tracking(&mut self, tile_size: Option<usize>) {
+        self.activity = tile_size.map(|tile_size| {
+            assert!(
+                self.rule.is_deterministic(),
+                "Only tiles of deterministic rules can be skipped!"
+            );
+            assert!(
+                tile_size >= self.board.neighborhood().radius(),
+                "Tiles have to be at least as large as the radius of the neighborhood!"
+            );
+            let (width, height) = self.board.dim();
+            Activity::new(width, height, tile_size)
+        });
+    }
+    /// Replace the seed the random numbers handed to the rule are derived from, for example to replay a game
+    /// whose initial state was not random. The random initial state of a game stays as it is.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.seeded = true;
+    }
+    /// Get the size of the tiles whose activity is tracked, if it is tracked
+    pub fn activity_tracking(&self) -> Option<usize> {
+        self.activity
+            .as_ref()
+            .map(|activity| activity.changed.tile_size())
+    }
 }
 
-impl<C: Cell, R: GameRule<Cell = C>> traits::CellGame for CellGame<C, R> {
+#[cfg(feature = "serde")]
+impl<C, R> CellGame<C, R>
+where
+    C: Cell + serde::Serialize + serde::de::DeserializeOwned,
+    R: ContextGameRule<Cell = C>,
+{
+    /// Construct a game of the given rule continuing from the board, generation and seed of the snapshot
+    pub fn from_snapshot(snapshot: &crate::snapshot::Snapshot<C>, rule: R) -> Self {
+        let mut game = Self::with_rule(snapshot.to_board(), rule);
+        game.generation = snapshot.generation();
+        if let Some(seed) = snapshot.seed() {
+            game.set_seed(seed);
+        }
+        game
+    }
+}
+
+impl<C: RandomCell, R: ContextGameRule<Cell = C>> traits::RandCellGame for CellGame<C, R> {
+    fn new_rand_seeded(
+        width: usize,
+        height: usize,
+        boundary: Boundary<Self::Cell>,
+        seed: u64,
+    ) -> Self {
+        let board = GameBoard::new_rand_seeded(width, height, boundary, seed);
+        Self {
+            seed,
+            seeded: true,
+            ..Self::from_board(board)
+        }
+    }
+}
+
+impl<C: Cell, R: ContextGameRule<Cell = C>> traits::CellGame for CellGame<C, R> {
     type Cell = C;
 
     type GameRule = R;
@@ -44,15 +231,59 @@ impl<C: Cell, R: GameRule<Cell = C>> traits::CellGame for CellGame<C, R> {
     fn get_board(&self) -> &GameBoard<Self::Cell> {
         &self.board
     }
+    fn update_board(&mut self) {
+        if let Some(activity) = &mut self.activity {
+            std::mem::swap(&mut activity.rendered, &mut activity.pending);
+            activity.pending.clear();
+        }
+    }
+    /// The seed is only returned if the initial state was random, the seed was set,
+    /// or the rule is not deterministic and might draw random numbers.
+    fn seed(&self) -> Option<u64> {
+        (self.seeded || !self.rule.is_deterministic()).then_some(self.seed)
+    }
+    fn dirty_tiles(&self) -> Option<&DirtyTiles> {
+        self.activity.as_ref().map(|activity| &activity.rendered)
+    }
+    fn set_rule_str(&mut self, rule: &str) -> Result<(), ParseRuleError> {
+        self.set_rule(R::parse_rule(rule)?);
+        Ok(())
+    }
     fn step(&mut self) {
-        self.scratch_board
-            .iter_mut()
-            .zip(self.rule.iter(&self.board))
-            .for_each(|(scratch_cell, cell)| *scratch_cell = cell);
+        let (rule, seed, generation) = (&self.rule, self.seed, self.generation);
+        let apply = |x, y, cell: &C, neighbors: Neighborhood<C>| {
+            let mut context = RuleContext::new(seed, generation, x, y);
+            rule.apply_in_context(cell, neighbors, &mut context)
+        };
+        if let Some(activity) = &mut self.activity {
+            let radius = self.board.neighborhood().radius();
+            let local_edges = !matches!(self.board.boundary(), Boundary::Toroidal);
+            let changed = self.scratch_board.fill_tiles_from_neighborhoods(
+                &self.board,
+                activity.changed.tile_size(),
+                &activity.changed.affected(radius, local_edges),
+                self.parallel,
+                apply,
+            );
+            activity.changed.set_flags(changed);
+            activity.pending.merge(&activity.changed);
+        } else {
+            self.scratch_board
+                .fill_from_neighborhoods(&self.board, self.parallel, apply);
+        }
+        self.scratch_board.update_border();
         self.board.swap(&mut self.scratch_board);
+        self.generation += 1;
+    }
+    fn generation(&self) -> u64 {
+        self.generation
     }
     fn clear(&mut self, clear_cell: Self::Cell) {
         self.board.iter_mut().for_each(|cell| *cell = clear_cell);
+        self.board.update_border();
+        if let Some(activity) = &mut self.activity {
+            activity.mark_all();
+        }
     }
     fn dim(&self) -> (usize, usize) {
         self.board.dim()
@@ -60,16 +291,188 @@ impl<C: Cell, R: GameRule<Cell = C>> traits::CellGame for CellGame<C, R> {
     fn next_cell(&mut self, x: usize, y: usize) {
         let cur_cell = self.board.get(x, y).expect("Index out of bounds!");
         let next_cell = cur_cell.next();
-        // let neighbor_iter = self.board.iter_neighbors(x, y);
-        // let next_cell = Self::GameRule::apply(cur_cell, neighbor_iter);
         self.board.set(x, y, next_cell);
+        if let Some(activity) = &mut self.activity {
+            activity.mark_cell(x, y);
+        }
     }
-    fn new(width: usize, height: usize, border_cell: Self::Cell) -> Self {
-        let board = GameBoard::<C>::new(width, height, border_cell);
-        Self {
-            rule: R::default(),
-            board: board.clone(),
-            scratch_board: board,
+    fn with_boundary(
+        width: usize,
+        height: usize,
+        cell: Self::Cell,
+        boundary: Boundary<Self::Cell>,
+    ) -> Self {
+        Self::from_board(GameBoard::with_boundary(width, height, cell, boundary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::traits::{CellGame as _, RandCellGame as _};
+    use super::*;
+    use crate::cell::mock::*;
+
+    #[test]
+    fn test_parallel_step_matches_serial() {
+        for boundary in [Boundary::Fixed(CellConway::Dead), Boundary::Toroidal] {
+            let mut serial =
+                CellGame::<CellConway, ConwayRule>::new_rand_with_boundary(97, 61, boundary);
+            let mut parallel = CellGame::<CellConway, ConwayRule> {
+                rule: ConwayRule::default(),
+                board: serial.board.clone(),
+                scratch_board: serial.scratch_board.clone(),
+                parallel: true,
+                activity: None,
+                seed: serial.seed,
+                seeded: true,
+                generation: 0,
+            };
+            for _ in 0..50 {
+                serial.step();
+                parallel.step();
+                assert!(serial.get_board().iter().eq(parallel.get_board().iter()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_activity_tracking_matches_full_step() {
+        // Gliders wrapping around the edges of the board, with tiles cut off at the edges
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        for (boundary, parallel) in [
+            (Boundary::Fixed(CellConway::Dead), false),
+            (Boundary::Toroidal, false),
+            (Boundary::Toroidal, true),
+        ] {
+            let (width, height) = (45, 30);
+            let mut full = CellGame::<CellConway, ConwayRule>::with_boundary(
+                width,
+                height,
+                CellConway::Dead,
+                boundary,
+            );
+            let mut tracked = CellGame::<CellConway, ConwayRule>::with_boundary(
+                width,
+                height,
+                CellConway::Dead,
+                boundary,
+            );
+            tracked.set_activity_tracking(Some(8));
+            tracked.set_parallel(parallel);
+            for &(x, y) in &glider {
+                full.next_cell(x + 20, y + 10);
+                tracked.next_cell(x + 20, y + 10);
+            }
+            // Every tile starts out dirty
+            tracked.update_board();
+            for _ in 0..150 {
+                full.step();
+                tracked.step();
+                tracked.update_board();
+                assert!(full.get_board().iter().eq(tracked.get_board().iter()));
+                // A glider fits into 3x3 cells, so it touches at most four tiles
+                assert!(tracked.dirty_tiles().unwrap().count() <= 4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed_reproduces_game() {
+        let boundary = Boundary::Toroidal;
+        let mut game = CellGame::<CellConway, ConwayRule>::new_rand_with_boundary(40, 30, boundary);
+        let seed = game.seed().unwrap();
+        let mut replay =
+            CellGame::<CellConway, ConwayRule>::new_rand_seeded(40, 30, boundary, seed);
+        assert!(game.get_board().iter().eq(replay.get_board().iter()));
+        // Nothing is random about a deterministic rule played on a given board
+        let board = replay.get_board().clone();
+        let mut fixed = CellGame::<CellConway, ConwayRule>::from_board(board.clone());
+        assert_eq!(fixed.seed(), None);
+        fixed.set_seed(seed);
+        assert_eq!(fixed.seed(), Some(seed));
+        assert!(CellGame::<CellConway, NoisyConwayRule>::from_board(board)
+            .seed()
+            .is_some());
+        for _ in 0..10 {
+            game.step();
+            replay.step();
+        }
+        assert!(game.get_board().iter().eq(replay.get_board().iter()));
+    }
+
+    /// Every alive cell dies with a probability of one in four, the rest follows Conway's Game of Life
+    #[derive(Default, Clone, Copy)]
+    struct NoisyConwayRule;
+    impl StochasticGameRule for NoisyConwayRule {
+        type Cell = CellConway;
+        fn apply_with_rng(
+            cell: &CellConway,
+            neighbors: Neighborhood<CellConway>,
+            rng: &mut CellRng,
+        ) -> CellConway {
+            use rand::Rng;
+            match ConwayRule::apply(cell, neighbors) {
+                CellConway::Alive if rng.gen_bool(0.25) => CellConway::Dead,
+                next => next,
+            }
+        }
+    }
+
+    #[test]
+    fn test_stochastic_rule_replays() {
+        let boundary = Boundary::Toroidal;
+        let mut serial =
+            CellGame::<CellConway, NoisyConwayRule>::new_rand_with_boundary(50, 40, boundary);
+        let seed = serial.seed().unwrap();
+        let mut parallel =
+            CellGame::<CellConway, NoisyConwayRule>::new_rand_seeded(50, 40, boundary, seed);
+        parallel.set_parallel(true);
+        let mut deterministic =
+            CellGame::<CellConway, ConwayRule>::new_rand_seeded(50, 40, boundary, seed);
+        for _ in 0..20 {
+            serial.step();
+            parallel.step();
+            deterministic.step();
+        }
+        assert!(serial.get_board().iter().eq(parallel.get_board().iter()));
+        assert!(!serial
+            .get_board()
+            .iter()
+            .eq(deterministic.get_board().iter()));
+    }
+
+    /// Fills stripes of columns of the given width, shifting by one stripe with every generation
+    #[derive(Default, Clone)]
+    struct StripesRule {
+        width: usize,
+    }
+    impl ContextGameRule for StripesRule {
+        type Cell = CellConway;
+        fn apply_in_context(
+            &self,
+            _cell: &CellConway,
+            _neighbors: Neighborhood<CellConway>,
+            context: &mut RuleContext,
+        ) -> CellConway {
+            if (context.x / self.width) as u64 % 2 == context.generation % 2 {
+                CellConway::Alive
+            } else {
+                CellConway::Dead
+            }
+        }
+    }
+
+    #[test]
+    fn test_rule_context() {
+        let board = GameBoard::new(12, 3, CellConway::Dead);
+        let mut game = CellGame::with_rule(board, StripesRule { width: 3 });
+        for generation in 0..4 {
+            game.step();
+            assert_eq!(game.generation(), generation + 1);
+            for (i, cell) in game.get_board().iter().enumerate() {
+                let alive = (i % 12 / 3) as u64 % 2 == generation % 2;
+                assert_eq!(*cell == CellConway::Alive, alive);
+            }
         }
     }
 }
@@ -1,8 +1,24 @@
+//! The GameBoard holding the cells of a game, surrounded by a border of cells as thick as the radius of
+//! the neighborhood, so the neighbors of edge cells can be looked up like those of any other cell.
+//!
+//! The border mirrors the edges of the board according to its Boundary. `set` keeps it up to date,
+//! but cells changed through `get_mut` or `iter_mut` are not seen by the neighbors across the boundary
+//! until `update_border` is called. Call it after every batch of such changes on boards with a Toroidal
+//! or Reflective boundary, otherwise the next step reads stale border cells. Fixed and Sides boundaries
+//! fill the border with constant cells, which stay valid.
+
+pub mod boundary;
 pub mod iter;
-use std::usize;
+pub mod neighborhood;
+pub mod tiles;
 
 use crate::cell::*;
+pub use boundary::{BorderSource, Boundary};
 use iter::*;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+pub use neighborhood::{Direction, Neighborhood, NeighborhoodShape, StateHistogram, Topology};
+pub use tiles::DirtyTiles;
 
 #[derive(Default, Debug, Clone)]
 pub struct GameBoard<C: Cell> {
@@ -10,45 +26,64 @@ pub struct GameBoard<C: Cell> {
     // outer_height can be calculated if needed: cells.len() / outer_width 
     width: usize,
     height: usize,
+    // Thickness of the border, which is the radius of the neighborhood
+    padding: usize,
     cells: Vec<C>,
-    neighbor_lookup: [isize; 8],
+    neighbor_lookup: Vec<isize>,
+    neighborhood: NeighborhoodShape,
+    boundary: Boundary<C>,
 }
 
 /// Public impl
-impl<'a, C: Cell> GameBoard<C> {
+impl<C: Cell> GameBoard<C> {
 
     /// Construct a new GameBoard with the given width and height and the passed cell variant 
     /// as the cell lining the border of the GameBoard. The border cell is fixed and cannot be updated.
     pub fn new(width: usize, height: usize, border_cell: C) -> Self {
-        let outer_width = width + 2;
-        let outer_height = height + 2;
+        Self::with_boundary(width, height, border_cell, Boundary::Fixed(border_cell))
+    }
+    /// Construct a new GameBoard with the given width and height filled with the passed cell variant.
+    /// The neighbors of cells at the edge of the GameBoard are determined by the given boundary.
+    /// Panics if the width or the height is zero.
+    pub fn with_boundary(width: usize, height: usize, cell: C, boundary: Boundary<C>) -> Self {
+        assert!(width > 0 && height > 0, "A GameBoard needs at least one cell, not {}x{}!", width, height);
+        let neighborhood = NeighborhoodShape::default();
+        let padding = neighborhood.radius();
+        let outer_width = width + 2 * padding;
+        let outer_height = height + 2 * padding;
         let num_nodes = outer_width * outer_height;
-        Self {
+        let mut board = Self {
             outer_width,
 
             width,
             height,
-            cells: vec![border_cell; num_nodes],
-            neighbor_lookup: build_neighbor_lookup(outer_width),
-        }
+            padding,
+            cells: vec![cell; num_nodes],
+            neighbor_lookup: build_neighbor_lookup(outer_width, &neighborhood),
+            neighborhood,
+            boundary,
+        };
+        board.update_border();
+        board
     }
     /// Construct a GameBoardIterator to iterate over the GameBoard cells.
     pub fn iter(&self) -> GameBoardIterator<'_, C> {
-        let offset = self.outer_width + 1;
-        let end = self.cells.len() - self.outer_width - 1;
+        let offset = self.index_inner_to_outer(0);
+        let end = self.index_inner_to_outer(self.width * self.height - 1) + 1;
         GameBoardIterator::new(self, offset, end)
     }
     /// Construct a mutable GameBoardIterator to iterate over the GameBoard cells.
+    /// Call `update_border` after changing cells through it.
     pub fn iter_mut(&mut self) -> GameBoardMutIterator<'_, C> {
-        let offset = self.outer_width + 1;
-        let end = self.cells.len() - self.outer_width - 1;
+        let offset = self.index_inner_to_outer(0);
+        let end = self.index_inner_to_outer(self.width * self.height - 1) + 1;
         GameBoardMutIterator::new(self, offset, end)
     }
     /// Construct a NeighborhoodIterator to iterate over the cells surounding the cell at the given coordinates.
-    pub fn iter_neighbors(&self, x: usize, y: usize) -> NeighborhoodIterator<C> {
+    pub fn iter_neighbors(&self, x: usize, y: usize) -> NeighborhoodIterator<'_, C> {
         // Allow only indexing into the inner parts of the board
-        assert!(check_not_border(x, y, self.width, self.height));
-        let (x, y) = coord_inner_to_outer(x, y);
+        assert!(check_inner(x, y, self.width, self.height));
+        let (x, y) = coord_inner_to_outer(x, y, self.padding);
         NeighborhoodIterator::new(
             self,
             coord_to_index(x, y, self.outer_width) as isize,
@@ -56,37 +91,48 @@ impl<'a, C: Cell> GameBoard<C> {
         )
     }
     /// Construct a NeighborhoodIterator to iterate over the cells surounding the cell at position given by the index. The index 
-    pub fn iter_neighbors_index(&self, i: usize) -> NeighborhoodIterator<C> {
+    pub fn iter_neighbors_index(&self, i: usize) -> NeighborhoodIterator<'_, C> {
         // Allow only indexing into the inner parts of the board
         let (x,y) = index_to_coord(i, self.width);
-        assert!(check_not_border(x, y, self.width, self.height));
+        assert!(check_inner(x, y, self.width, self.height));
         let i = self.index_inner_to_outer(i);
         self.iter_neighbors_index_outer(i)
     }
+    /// Get the neighbors of the cell at the given coordinates.
+    pub fn neighbors(&self, x: usize, y: usize) -> Neighborhood<'_, C> {
+        assert!(check_inner(x, y, self.width, self.height));
+        let (x, y) = coord_inner_to_outer(x, y, self.padding);
+        Neighborhood::new(self, coord_to_index(x, y, self.outer_width))
+    }
     /// Construct a LocalGroupIterator to iterate over all neighborhoods on the GameBoard.
-    pub fn iter_local_groups(&self) -> LocalGroupIterator<C> {
+    pub fn iter_local_groups(&self) -> LocalGroupIterator<'_, C> {
         LocalGroupIterator::new(self,self.index_inner_to_outer(0))
     }
     /// Get a shared borrow of the cell at the given coordinate
     pub fn get(&self, x: usize, y: usize) -> Option<&C> {
-        assert!(check_not_border(x, y, self.width, self.height));
-        let(x,y) = coord_inner_to_outer(x, y);
+        assert!(check_inner(x, y, self.width, self.height));
+        let(x,y) = coord_inner_to_outer(x, y, self.padding);
         let i = coord_to_index(x, y, self.outer_width);
         self.cells.get(i)
     }
-    /// Get a mutable borrow of the cell at the given coordinate
+    /// Get a mutable borrow of the cell at the given coordinate.
+    /// Call `update_border` after changing the cell, or use `set` which does so on its own.
     pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut C> {
-        assert!(check_not_border(x, y, self.width, self.height));
-        let(x,y) = coord_inner_to_outer(x, y);
+        assert!(check_inner(x, y, self.width, self.height));
+        let(x,y) = coord_inner_to_outer(x, y, self.padding);
         let i = coord_to_index(x, y, self.outer_width);
         self.cells.get_mut(i)
     }
     /// Set the cell at the given coordinate to the provided cell
     pub fn set(&mut self, x: usize, y: usize, cell: C) {
-        assert!(check_not_border(x, y, self.width, self.height));
-        let(x,y) = coord_inner_to_outer(x, y);
+        assert!(check_inner(x, y, self.width, self.height));
+        let is_edge = check_edge(x, y, self.width, self.height, self.padding);
+        let(x,y) = coord_inner_to_outer(x, y, self.padding);
         let i = coord_to_index(x, y, self.outer_width);
         self.cells[i] = cell;
+        if is_edge && !self.boundary.is_fixed() {
+            self.update_border();
+        }
     }
     /// Get a tuple containing the dimension of the GameBoard as (width, height)
     pub fn dim(&self) -> (usize, usize) {
@@ -97,58 +143,230 @@ impl<'a, C: Cell> GameBoard<C> {
         assert_eq!(self.dim(), other.dim(), "GameBoards cannot swap because of unequal dimension!");
         std::mem::swap(&mut self.cells, &mut other.cells);
     }
+    /// Get the boundary of the GameBoard
+    pub fn boundary(&self) -> &Boundary<C> {
+        &self.boundary
+    }
+    /// Replace the boundary of the GameBoard and rebuild the border accordingly
+    pub fn set_boundary(&mut self, boundary: Boundary<C>) {
+        self.boundary = boundary;
+        self.update_border();
+    }
+    /// Get the shape of the neighborhoods produced by the GameBoard
+    pub fn neighborhood(&self) -> &NeighborhoodShape {
+        &self.neighborhood
+    }
+    /// Get the kind of grid the cells are arranged on, which follows from the neighborhood
+    pub fn topology(&self) -> Topology {
+        self.neighborhood.topology()
+    }
+    /// Replace the shape of the neighborhoods produced by the GameBoard.
+    /// The border grows or shrinks to the radius of the new neighborhood, the inner cells are kept.
+    pub fn set_neighborhood(&mut self, neighborhood: NeighborhoodShape) {
+        let padding = neighborhood.radius();
+        if padding != self.padding {
+            let outer_width = self.width + 2 * padding;
+            let outer_height = self.height + 2 * padding;
+            // The border cells are overwritten according to the boundary below
+            let mut cells = vec![self.cells[0]; outer_width * outer_height];
+            for (i, cell) in self.iter().enumerate() {
+                let (x, y) = index_to_coord(i, self.width);
+                let (x, y) = coord_inner_to_outer(x, y, padding);
+                cells[coord_to_index(x, y, outer_width)] = *cell;
+            }
+            self.cells = cells;
+            self.outer_width = outer_width;
+            self.padding = padding;
+        }
+        self.neighbor_lookup = build_neighbor_lookup(self.outer_width, &neighborhood);
+        self.neighborhood = neighborhood;
+        self.update_border();
+    }
+    /// Refresh the border cells according to the boundary of the GameBoard.
+    /// `set` does this on its own, but after modifying cells through `get_mut` or `iter_mut`
+    /// this has to be called before the neighborhoods of the edge cells are correct again.
+    /// For fixed boundaries the border never changes and this can be skipped.
+    pub fn update_border(&mut self) {
+        let (outer_width, outer_height) = (self.outer_width, self.cells.len() / self.outer_width);
+        let padding = self.padding;
+        for y in 0..outer_height {
+            let is_border_row = y < padding || y >= outer_height - padding;
+            // Inner rows only have border cells in the first and last columns
+            let columns = (0..outer_width)
+                .filter(|&x| is_border_row || x < padding || x >= outer_width - padding);
+            for x in columns {
+                let (x_inner, y_inner) = (x as isize - padding as isize, y as isize - padding as isize);
+                let cell = match self.boundary.resolve(x_inner, y_inner, self.width, self.height) {
+                    BorderSource::Cell(cell) => cell,
+                    BorderSource::Inner(x_src, y_src) => {
+                        let (x_src, y_src) = coord_inner_to_outer(x_src, y_src, padding);
+                        self.cells[coord_to_index(x_src, y_src, outer_width)]
+                    }
+                };
+                self.cells[coord_to_index(x, y, outer_width)] = cell;
+            }
+        }
+    }
 }
-impl<'a, C: RandomCell> GameBoard<C> {
+impl<C: RandomCell> GameBoard<C> {
     pub fn new_rand(width: usize, height: usize, border_cell: C) -> Self {
-        let outer_width = width + 2;
-        let outer_height = height + 2;
+        Self::new_rand_with_boundary(width, height, Boundary::Fixed(border_cell))
+    }
+    pub fn new_rand_with_boundary(width: usize, height: usize, boundary: Boundary<C>) -> Self {
+        Self::new_rand_with_rng(width, height, boundary, &mut rand::thread_rng())
+    }
+    /// Construct a new GameBoard with random cells drawn from the given random number generator.
+    pub fn new_rand_with_rng<R: Rng + ?Sized>(
+        width: usize,
+        height: usize,
+        boundary: Boundary<C>,
+        rng: &mut R,
+    ) -> Self {
+        assert!(width > 0 && height > 0, "A GameBoard needs at least one cell, not {}x{}!", width, height);
+        let neighborhood = NeighborhoodShape::default();
+        let padding = neighborhood.radius();
+        let outer_width = width + 2 * padding;
+        let outer_height = height + 2 * padding;
         let num_nodes = outer_width * outer_height;
-        let mut cells = Vec::new();
-
-        cells.reserve(num_nodes);
-        for i in 0..(num_nodes) {
-            let (x, y) = index_to_coord(i, outer_width);
-            let is_border = check_border(x, y, outer_width, outer_height);
-            if is_border {
-                cells.push(border_cell)
-            } else {
-                cells.push(C::new_rand());
-            }
-        }
-        Self {
+        // The border cells are overwritten according to the boundary below
+        let cells = (0..num_nodes).map(|_| C::new_rand_with(rng)).collect();
+        let mut board = Self {
             outer_width,
             
             width,
             height,
+            padding,
             cells,
-            neighbor_lookup: build_neighbor_lookup(outer_width),
-        }
+            neighbor_lookup: build_neighbor_lookup(outer_width, &neighborhood),
+            neighborhood,
+            boundary,
+        };
+        board.update_border();
+        board
+    }
+    /// Construct a new GameBoard with random cells determined by the seed.
+    /// The same seed always produces the same GameBoard.
+    pub fn new_rand_seeded(width: usize, height: usize, boundary: Boundary<C>, seed: u64) -> Self {
+        Self::new_rand_with_rng(width, height, boundary, &mut SeedRng::seed_from_u64(seed))
     }
 }
 
 /// Private impl
-impl<'a, C: Cell> GameBoard<C> {
+impl<C: Cell> GameBoard<C> {
+    /// Overwrite every cell including the border with the cell produced for its inner coordinate.
+    /// The coordinates of border cells lie outside of 0..width and 0..height.
+    pub(crate) fn fill_outer(&mut self, mut cell_at: impl FnMut(isize, isize) -> C) {
+        let padding = self.padding as isize;
+        for (i, cell) in self.cells.iter_mut().enumerate() {
+            let (x, y) = index_to_coord(i, self.outer_width);
+            *cell = cell_at(x as isize - padding, y as isize - padding);
+        }
+    }
+    /// Overwrite every inner cell with the result of the function applied to the coordinate of the cell,
+    /// the cell at the same position on the source board and its neighborhood.
+    /// If requested, the rows are split into bands which are processed in parallel.
+    /// Both boards have to have the same dimensions and neighborhood.
+    pub(crate) fn fill_from_neighborhoods<F>(&mut self, source: &GameBoard<C>, parallel: bool, f: F)
+    where
+        F: Fn(usize, usize, &C, Neighborhood<'_, C>) -> C + Sync,
+    {
+        debug_assert_eq!(self.outer_width, source.outer_width);
+        debug_assert_eq!(self.padding, source.padding);
+        let (outer_width, width, padding) = (self.outer_width, self.width, self.padding);
+        // A few bands per thread even out the work when some rows are more expensive than others
+        let band_height = if parallel {
+            (self.height / (rayon::current_num_threads() * 4)).max(1)
+        } else {
+            self.height.max(1)
+        };
+        let fill_band = |(band, rows): (usize, &mut [C])| {
+            for (row_in_band, row) in rows.chunks_mut(outer_width).enumerate() {
+                let y = band * band_height + row_in_band;
+                let row_start = (padding + y) * outer_width;
+                for (x, cell) in row.iter_mut().enumerate().skip(padding).take(width) {
+                    let i = row_start + x;
+                    *cell = f(x - padding, y, &source.cells[i], Neighborhood::new(source, i));
+                }
+            }
+        };
+        let inner_rows = &mut self.cells[padding * outer_width..(padding + self.height) * outer_width];
+        if parallel {
+            inner_rows
+                .par_chunks_mut(band_height * outer_width)
+                .enumerate()
+                .for_each(fill_band);
+        } else {
+            inner_rows
+                .chunks_mut(band_height * outer_width)
+                .enumerate()
+                .for_each(fill_band);
+        }
+    }
+    /// Overwrite the inner cells of the tiles flagged in `compute` with the result of the function applied to
+    /// the coordinate of the cell, the cell at the same position on the source board and its neighborhood.
+    /// The flags are row by row for tiles of the given size. The rows of tiles are processed in parallel if requested.
+    /// Returns one flag per tile, row by row, telling whether a cell of the tile differs from the source board.
+    pub(crate) fn fill_tiles_from_neighborhoods<F>(
+        &mut self,
+        source: &GameBoard<C>,
+        tile_size: usize,
+        compute: &[bool],
+        parallel: bool,
+        f: F,
+    ) -> Vec<bool>
+    where
+        F: Fn(usize, usize, &C, Neighborhood<'_, C>) -> C + Sync,
+    {
+        debug_assert_eq!(self.outer_width, source.outer_width);
+        debug_assert_eq!(self.padding, source.padding);
+        let (outer_width, width, padding) = (self.outer_width, self.width, self.padding);
+        let tiles_x = width.div_ceil(tile_size);
+        let fill_tile_row = |(ty, rows): (usize, &mut [C])| {
+            let mut changed = vec![false; tiles_x];
+            for tx in (0..tiles_x).filter(|tx| compute[ty * tiles_x + tx]) {
+                let columns = padding + tx * tile_size..padding + ((tx + 1) * tile_size).min(width);
+                for (row_in_tile, row) in rows.chunks_mut(outer_width).enumerate() {
+                    let y = ty * tile_size + row_in_tile;
+                    let row_start = (padding + y) * outer_width;
+                    for x in columns.clone() {
+                        let i = row_start + x;
+                        row[x] = f(x - padding, y, &source.cells[i], Neighborhood::new(source, i));
+                        changed[tx] |= row[x] != source.cells[i];
+                    }
+                }
+            }
+            changed
+        };
+        let inner_rows = &mut self.cells[padding * outer_width..(padding + self.height) * outer_width];
+        if parallel {
+            inner_rows
+                .par_chunks_mut(tile_size * outer_width)
+                .enumerate()
+                .flat_map_iter(fill_tile_row)
+                .collect()
+        } else {
+            inner_rows
+                .chunks_mut(tile_size * outer_width)
+                .enumerate()
+                .flat_map(fill_tile_row)
+                .collect()
+        }
+    }
     #[allow(unused)]
     fn index_outer_to_inner(&self, i: usize) -> usize {
-        let i = i as isize;
-        let i = i
-            - self.outer_width as isize // Subtract upper border
-            - 2*(i / self.outer_width as isize) // Subtract right border cell of row above and left border cell of current row up to current one
-            + 1; // Correct previous statement for the right border cell of the upper row, which was subtracted as a whole 
-        i as usize
+        let (x, y) = index_to_coord(i, self.outer_width);
+        let (x, y) = coord_outer_to_inner(x, y, self.padding);
+        coord_to_index(x, y, self.width)
     }
     fn index_inner_to_outer(&self, i: usize) -> usize {
-        let i = i as isize;
-        let i = i 
-        + self.outer_width as isize // Add upper border row
-        + 2*(i / self.width as isize) // Add right border cell of row above and left border cell of current row up to current one
-        + 1; // Correct previous statement for the right border cell of the upper row, which is not included in the previous calculation
-        i as usize
+        let (x, y) = index_to_coord(i, self.width);
+        let (x, y) = coord_inner_to_outer(x, y, self.padding);
+        coord_to_index(x, y, self.outer_width)
     }
 
     /// Produces a NeighborhoodIterator for the cell at coordinates (x,y)
     #[allow(unused)]
-    fn iter_neighbors_outer(&self, x: usize, y: usize) -> NeighborhoodIterator<C> {
+    fn iter_neighbors_outer(&self, x: usize, y: usize) -> NeighborhoodIterator<'_, C> {
         NeighborhoodIterator::new(
             self,
             coord_to_index(x, y, self.outer_width) as isize,
@@ -157,7 +375,7 @@ impl<'a, C: Cell> GameBoard<C> {
     }
 
     /// Argument has to be a valid index into the inner part of the board
-    fn iter_neighbors_index_outer(&self, i: usize) -> NeighborhoodIterator<C> {
+    fn iter_neighbors_index_outer(&self, i: usize) -> NeighborhoodIterator<'_, C> {
         NeighborhoodIterator::new(self, i as isize, self.neighbor_lookup.iter())
     }
     fn get_index_outer(&self, i: usize) -> Option<&C> {
@@ -171,17 +389,12 @@ impl<'a, C: Cell> GameBoard<C> {
     }
 }
 
-fn build_neighbor_lookup(outer_width: usize) -> [isize; 8] {
-    [
-        -(outer_width as isize) - 1,
-        -(outer_width as isize),
-        -(outer_width as isize) + 1,
-        -1,
-        1,
-        (outer_width as isize) - 1,
-        (outer_width as isize),
-        (outer_width as isize) + 1,
-    ]
+fn build_neighbor_lookup(outer_width: usize, neighborhood: &NeighborhoodShape) -> Vec<isize> {
+    neighborhood
+        .offsets()
+        .into_iter()
+        .map(|(dx, dy)| dy * outer_width as isize + dx)
+        .collect()
 }
 
 fn index_to_coord(i: usize, width: usize) -> (usize, usize) {
@@ -192,18 +405,19 @@ fn index_to_coord(i: usize, width: usize) -> (usize, usize) {
 fn coord_to_index(x: usize, y: usize, width: usize) -> usize {
     y * width + x
 }
-fn check_not_border(x: usize, y: usize, width: usize, height: usize) -> bool {
-    x > 0 && x < (width - 1) && y > 0 && y < (height - 1)
+/// Whether the inner coordinate is close enough to the edge to show up in the border
+fn check_edge(x: usize, y: usize, width: usize, height: usize, padding: usize) -> bool {
+    x < padding || x + padding >= width || y < padding || y + padding >= height
 }
-fn check_border(x: usize, y: usize, width: usize, height: usize) -> bool {
-    !check_not_border(x, y, width, height)
+fn check_inner(x: usize, y: usize, width: usize, height: usize) -> bool {
+    x < width && y < height
 }
-fn coord_inner_to_outer(x: usize, y: usize) -> (usize, usize) {
-    (x + 1, y + 1)
+fn coord_inner_to_outer(x: usize, y: usize, padding: usize) -> (usize, usize) {
+    (x + padding, y + padding)
 }
 #[allow(unused)]
-fn coord_outer_to_inner(x: usize, y: usize) -> (usize, usize) {
-    (x - 1, y - 1)
+fn coord_outer_to_inner(x: usize, y: usize, padding: usize) -> (usize, usize) {
+    (x - padding, y - padding)
 }
 
 #[cfg(test)]
@@ -213,11 +427,13 @@ mod tests{
     #[test]
 fn test_coord_roundtrip() {
     let (width, height) = (5, 5);
-    for x_inner in 0..width {
-        for y_inner in 0..height {
-            let (x_outer, y_outer) = coord_inner_to_outer(x_inner, y_inner);
-            let (x_back, y_back) = coord_outer_to_inner(x_outer, y_outer);
-            assert!(x_back == x_inner && y_back == y_inner);
+    for padding in 0..3 {
+        for x_inner in 0..width {
+            for y_inner in 0..height {
+                let (x_outer, y_outer) = coord_inner_to_outer(x_inner, y_inner, padding);
+                let (x_back, y_back) = coord_outer_to_inner(x_outer, y_outer, padding);
+                assert!(x_back == x_inner && y_back == y_inner);
+            }
         }
     }
 }
@@ -230,4 +446,71 @@ fn test_index_roundtrip() {
         assert!(i_inner == board.index_outer_to_inner(i_outer));
     }
 }
+#[test]
+fn test_toroidal_neighbors_wrap() {
+    let (width, height) = (4, 3);
+    let mut board = GameBoard::with_boundary(width, height, CellConway::Dead, Boundary::Toroidal);
+    board.set(width - 1, height - 1, CellConway::Alive);
+    let alive = board.iter_neighbors(0, 0).filter(|&&c| c == CellConway::Alive).count();
+    assert_eq!(alive, 1);
+    let alive = board.iter_neighbors(0, height - 1).filter(|&&c| c == CellConway::Alive).count();
+    assert_eq!(alive, 1);
+}
+#[test]
+fn test_reflective_and_side_borders() {
+    let (width, height) = (3, 3);
+    let mut board = GameBoard::with_boundary(width, height, CellConway::Dead, Boundary::Reflective);
+    board.set(0, 1, CellConway::Alive);
+    // The border cell left of (0, 1) mirrors (0, 1), so it is seen twice from (0, 0)
+    let alive = board.iter_neighbors(0, 0).filter(|&&c| c == CellConway::Alive).count();
+    assert_eq!(alive, 2);
+    board.set_boundary(Boundary::Sides {
+        top: CellConway::Alive,
+        bottom: CellConway::Dead,
+        left: CellConway::Dead,
+        right: CellConway::Alive,
+    });
+    let alive = board.iter_neighbors(2, 0).filter(|&&c| c == CellConway::Alive).count();
+    assert_eq!(alive, 5);
+}
+#[test]
+#[should_panic(expected = "at least one cell")]
+fn test_empty_board() {
+    GameBoard::with_boundary(0, 4, CellConway::Dead, Boundary::Toroidal);
+}
+#[test]
+fn test_neighborhood_radius() {
+    let (width, height) = (5, 6);
+    let mut board = GameBoard::with_boundary(width, height, CellConway::Dead, Boundary::Toroidal);
+    board.set(4, 5, CellConway::Alive);
+    board.set_neighborhood(NeighborhoodShape::Moore(2));
+    assert_eq!(board.iter().count(), width * height);
+    assert_eq!(*board.get(4, 5).unwrap(), CellConway::Alive);
+    assert_eq!(board.iter_neighbors(1, 1).count(), 24);
+    // (4, 5) is two cells away from (1, 1) across both edges
+    let alive = board.iter_neighbors(1, 1).filter(|&&c| c == CellConway::Alive).count();
+    assert_eq!(alive, 1);
+    board.set_neighborhood(NeighborhoodShape::VonNeumann(2));
+    let alive = board.iter_neighbors(1, 1).filter(|&&c| c == CellConway::Alive).count();
+    assert_eq!(alive, 0);
+    assert_eq!(NeighborhoodShape::Hexagonal.offsets().len(), 6);
+}
+#[test]
+fn test_neighborhood_directions() {
+    let mut board = GameBoard::new(3, 3, CellConway::Dead);
+    board.set(2, 0, CellConway::Alive);
+    let neighbors = board.neighbors(1, 1);
+    assert_eq!(neighbors[Direction::NE], CellConway::Alive);
+    assert_eq!(neighbors[Direction::NE.opposite()], CellConway::Dead);
+    assert_eq!(neighbors.count(&CellConway::Alive), 1);
+    let histogram = neighbors.histogram();
+    assert_eq!(histogram.count(&CellConway::Dead), 7);
+    assert_eq!(histogram.counts(), &[7, 1]);
+    // On hexagonal boards the top right neighbor lies to the north east as well
+    board.set_neighborhood(NeighborhoodShape::Hexagonal);
+    let neighbors = board.neighbors(1, 1);
+    assert_eq!(neighbors.get(Direction::N), None);
+    assert_eq!(neighbors[Direction::NE], CellConway::Alive);
+    assert_eq!(neighbors.len(), 6);
+}
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+use crate::cell::Cell;
+
+/// Describes what the neighbors of cells at the edge of a GameBoard look like.
+///
+/// The GameBoard is lined with border cells, which the NeighborhoodIterator and LocalGroupIterator
+/// read like any other cell. The boundary decides what each of these border cells holds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Boundary<C: Cell> {
+    /// The board is lined with the given cell. The border cell is fixed and cannot be updated.
+    Fixed(C),
+    /// Every side of the board is lined with its own fixed cell.
+    /// The corners belong to the top and bottom rows.
+    Sides {
+        top: C,
+        bottom: C,
+        left: C,
+        right: C,
+    },
+    /// The board wraps around, so the neighbors beyond one edge are the cells at the opposite edge.
+    Toroidal,
+    /// The board is mirrored at its edges, so the neighbors beyond one edge are the nearest inner cells.
+    Reflective,
+}
+
+/// The content of a single border cell as decided by a Boundary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BorderSource<C: Cell> {
+    /// The border cell is the given fixed cell.
+    Cell(C),
+    /// The border cell mirrors the inner cell at the given coordinates.
+    Inner(usize, usize),
+}
+
+impl<C: Cell + Default> Default for Boundary<C> {
+    fn default() -> Self {
+        Self::Fixed(C::default())
+    }
+}
+
+impl<C: Cell> Boundary<C> {
+    /// Decide the content of the border cell at a coordinate outside the inner board of the given dimension.
+    pub fn resolve(&self, x: isize, y: isize, width: usize, height: usize) -> BorderSource<C> {
+        let (w, h) = (width as isize, height as isize);
+        match *self {
+            Self::Fixed(cell) => BorderSource::Cell(cell),
+            Self::Sides { top, .. } if y < 0 => BorderSource::Cell(top),
+            Self::Sides { bottom, .. } if y >= h => BorderSource::Cell(bottom),
+            Self::Sides { left, .. } if x < 0 => BorderSource::Cell(left),
+            Self::Sides { right, .. } => BorderSource::Cell(right),
+            Self::Toroidal => {
+                BorderSource::Inner(x.rem_euclid(w) as usize, y.rem_euclid(h) as usize)
+            }
+            Self::Reflective => BorderSource::Inner(reflect(x, w), reflect(y, h)),
+        }
+    }
+    /// Whether the border cells only depend on the boundary and not on the inner cells.
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, Self::Fixed(_) | Self::Sides { .. })
+    }
+}
+
+/// Mirror a coordinate at the edges of the range 0..len, so -1 maps to 0 and len maps to len - 1.
+fn reflect(i: isize, len: isize) -> usize {
+    let i = if i < 0 {
+        -i - 1
+    } else if i >= len {
+        2 * len - i - 1
+    } else {
+        i
+    };
+    i.clamp(0, len - 1) as usize
+}
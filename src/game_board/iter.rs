@@ -18,7 +18,8 @@ impl<'a, C: Cell> Iterator for GameBoardIterator<'a, C> {
         //     self.offset += 2;
         //     debug_assert!(self.offset < self.end, "GameBoardIterator in invalid state!");
         // }
-        self.offset = outer_index_skip_border(self.offset, self.board.outer_width);
+        self.offset =
+            outer_index_skip_border(self.offset, self.board.outer_width, self.board.padding);
         // Bound checks are done above and should not be repeated
         let result = unsafe { self.board.get_unchecked_index_outer(self.offset) };
         self.offset += 1;
@@ -47,7 +48,8 @@ impl<'a, C: Cell> Iterator for GameBoardMutIterator<'a, C> {
         if self.offset == self.end {
             return None;
         }
-        self.offset = outer_index_skip_border(self.offset, self.board.outer_width);
+        self.offset =
+            outer_index_skip_border(self.offset, self.board.outer_width, self.board.padding);
         // Bound checks are done above and should not be repeated
         // Use unsafe pointer to convince the compiler that no two calls to next result in a reference to the same object
         let ptr = unsafe { self.board.get_unchecked_mut_index_outer(self.offset) as *mut C };
@@ -160,13 +162,14 @@ pub struct LocalGroupIterator<'a, C: Cell> {
 }
 
 impl<'a, C: Cell> Iterator for LocalGroupIterator<'a, C> {
-    type Item = (&'a C, NeighborhoodIterator<'a, C>);
+    type Item = (&'a C, Neighborhood<'a, C>);
     fn next(&mut self) -> Option<Self::Item> {
-        self.center = outer_index_skip_border(self.center, self.board.outer_width);
+        self.center =
+            outer_index_skip_border(self.center, self.board.outer_width, self.board.padding);
         // Bound checks are done above and should not be repeated
         let result = Some((
-            unsafe { self.board.get_unchecked_index_outer(self.center as usize) },
-            self.board.iter_neighbors_index_outer(self.center),
+            unsafe { self.board.get_unchecked_index_outer(self.center) },
+            Neighborhood::new(self.board, self.center),
         ));
         self.center += 1;
         result
@@ -178,6 +181,6 @@ impl<'a, C: Cell> LocalGroupIterator<'a, C> {
     }
 }
 
-fn outer_index_skip_border(index: usize, outer_width: usize) -> usize {
-    index + 2 * ((index % outer_width) == (outer_width - 1)) as usize
+fn outer_index_skip_border(index: usize, outer_width: usize, padding: usize) -> usize {
+    index + 2 * padding * ((index % outer_width) == (outer_width - padding)) as usize
 }
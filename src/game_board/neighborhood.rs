@@ -0,0 +1,279 @@
+use std::marker::PhantomData;
+use std::ops::Index;
+
+use super::iter::NeighborhoodIterator;
+use super::GameBoard;
+use crate::cell::{Cell, FiniteCell};
+
+/// Describes which cells around a cell make up its neighborhood.
+///
+/// Offsets are given as (dx, dy) relative to the center cell, with y pointing downwards.
+/// The neighbors are always iterated in the order returned by `offsets`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NeighborhoodShape {
+    /// All cells within the given Chebyshev distance, so radius 1 are the eight surrounding cells.
+    Moore(usize),
+    /// All cells within the given Manhattan distance, so radius 1 are the four orthogonal cells.
+    VonNeumann(usize),
+    /// The six neighbors of a hexagonal grid stored in axial coordinates on a square grid,
+    /// which are the Moore neighbors without the top left and bottom right cell.
+    Hexagonal,
+    /// A user supplied list of offsets.
+    Custom(Vec<(isize, isize)>),
+}
+
+/// The kind of grid the cells of a GameBoard are arranged on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Cell (x, y) is a square in column x and row y.
+    Square,
+    /// Cell (x, y) is a hexagon at the axial coordinates q = x and r = y.
+    /// Row r is shifted by half a cell to the right relative to row r - 1, so the board is a parallelogram.
+    Hexagonal,
+}
+
+impl Default for NeighborhoodShape {
+    fn default() -> Self {
+        Self::Moore(1)
+    }
+}
+
+impl NeighborhoodShape {
+    /// The offsets of all neighbors. Apart from Custom, they are ordered row by row starting at the top left.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        match self {
+            Self::Moore(radius) => square_offsets(*radius)
+                .filter(|(dx, dy)| dx.abs().max(dy.abs()) <= *radius as isize)
+                .collect(),
+            Self::VonNeumann(radius) => square_offsets(*radius)
+                .filter(|(dx, dy)| dx.abs() + dy.abs() <= *radius as isize)
+                .collect(),
+            Self::Hexagonal => square_offsets(1)
+                .filter(|&offset| offset != (-1, -1) && offset != (1, 1))
+                .collect(),
+            Self::Custom(offsets) => offsets.clone(),
+        }
+    }
+    /// The kind of grid this neighborhood implies for the GameBoard.
+    pub fn topology(&self) -> Topology {
+        match self {
+            Self::Hexagonal => Topology::Hexagonal,
+            _ => Topology::Square,
+        }
+    }
+    /// The largest distance of a neighbor along either axis. The GameBoard border is this thick.
+    pub fn radius(&self) -> usize {
+        match self {
+            Self::Moore(radius) | Self::VonNeumann(radius) => *radius,
+            Self::Hexagonal => 1,
+            Self::Custom(offsets) => offsets
+                .iter()
+                .map(|(dx, dy)| dx.unsigned_abs().max(dy.unsigned_abs()))
+                .max()
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// All offsets of the square with the given radius except the center, ordered row by row
+fn square_offsets(radius: usize) -> impl Iterator<Item = (isize, isize)> {
+    let radius = radius as isize;
+    (-radius..=radius)
+        .flat_map(move |dy| (-radius..=radius).map(move |dx| (dx, dy)))
+        .filter(|&offset| offset != (0, 0))
+}
+
+/// A direction on a GameBoard, with north pointing to the top row.
+///
+/// On hexagonal boards the six directions other than N and S lead to the six neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    /// All directions, clockwise starting at north
+    pub const ALL: [Direction; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+    /// The offset (dx, dy) of the neighbor in this direction, with y pointing downwards.
+    /// Returns None for N and S on hexagonal boards, which have no neighbor in these directions.
+    pub fn offset(self, topology: Topology) -> Option<(isize, isize)> {
+        match topology {
+            Topology::Square => Some(match self {
+                Self::N => (0, -1),
+                Self::NE => (1, -1),
+                Self::E => (1, 0),
+                Self::SE => (1, 1),
+                Self::S => (0, 1),
+                Self::SW => (-1, 1),
+                Self::W => (-1, 0),
+                Self::NW => (-1, -1),
+            }),
+            // Every row is shifted by half a cell to the right relative to the row above
+            Topology::Hexagonal => match self {
+                Self::N | Self::S => None,
+                Self::NE => Some((1, -1)),
+                Self::E => Some((1, 0)),
+                Self::SE => Some((0, 1)),
+                Self::SW => Some((-1, 1)),
+                Self::W => Some((-1, 0)),
+                Self::NW => Some((0, -1)),
+            },
+        }
+    }
+    /// The direction pointing the other way
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+/// The neighbors of a single cell on a GameBoard, as handed to game rules.
+///
+/// The neighbors can be iterated in the order given by `NeighborhoodShape::offsets`, or looked up by
+/// their Direction or offset, independent of that order.
+/// For cells with finitely many states, `histogram` counts every state at once, after which each count is O(1).
+pub struct Neighborhood<'a, C: Cell> {
+    board: &'a GameBoard<C>,
+    // Index of the center cell into the cells of the board, including the border
+    center: usize,
+}
+
+impl<'a, C: Cell> Clone for Neighborhood<'a, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<'a, C: Cell> Copy for Neighborhood<'a, C> {}
+
+impl<'a, C: Cell> Neighborhood<'a, C> {
+    pub(crate) fn new(board: &'a GameBoard<C>, center: usize) -> Self {
+        Self { board, center }
+    }
+    /// Iterate over the neighbors in the order given by `NeighborhoodShape::offsets`
+    pub fn iter(&self) -> NeighborhoodIterator<'a, C> {
+        NeighborhoodIterator::new(
+            self.board,
+            self.center as isize,
+            self.board.neighbor_lookup.iter(),
+        )
+    }
+    /// Get the number of neighbors
+    pub fn len(&self) -> usize {
+        self.board.neighbor_lookup.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Get the neighbor in the given direction. Returns None if the board has no neighbor in this direction.
+    ///
+    /// The neighbor is looked up even if the shape of the neighborhood does not contain it,
+    /// as long as the neighborhood reaches at least one cell in every direction.
+    pub fn get(&self, direction: Direction) -> Option<&'a C> {
+        let (dx, dy) = direction.offset(self.board.topology())?;
+        Some(self.at(dx, dy))
+    }
+    /// Get the cell at the given offset from the center, with y pointing downwards.
+    /// Panics if the offset reaches further than the radius of the neighborhood.
+    pub fn at(&self, dx: isize, dy: isize) -> &'a C {
+        let radius = self.board.padding as isize;
+        assert!(
+            dx.abs() <= radius && dy.abs() <= radius,
+            "Offset ({}, {}) lies outside of the neighborhood!",
+            dx,
+            dy
+        );
+        let i = self.center as isize + dy * self.board.outer_width as isize + dx;
+        &self.board.cells[i as usize]
+    }
+    /// Count the neighbors in the given state by scanning all neighbors.
+    /// Rules counting several states of a FiniteCell should use `histogram` instead.
+    pub fn count(&self, state: &C) -> usize {
+        self.iter().filter(|&cell| cell == state).count()
+    }
+    /// Count the neighbors fulfilling the predicate
+    pub fn count_where(&self, predicate: impl Fn(&C) -> bool) -> usize {
+        self.iter().filter(|&cell| predicate(cell)).count()
+    }
+    /// Get the rows of the square around the center reaching as far as the radius of the board, top to bottom.
+    pub(crate) fn square_rows(&self) -> impl Iterator<Item = &'a [C]> {
+        let (side, width) = (2 * self.board.padding + 1, self.board.outer_width);
+        let start = self.center - self.board.padding * (width + 1);
+        let cells = &self.board.cells;
+        (0..side).map(move |row| &cells[start + row * width..start + row * width + side])
+    }
+}
+
+impl<'a, C: FiniteCell> Neighborhood<'a, C> {
+    /// Count the neighbors in every state in a single pass, without allocating.
+    /// Panics if the cell has more than `StateHistogram::MAX_STATES` states.
+    pub fn histogram(&self) -> StateHistogram<C> {
+        assert!(
+            C::STATE_COUNT <= StateHistogram::<C>::MAX_STATES,
+            "A histogram supports at most {} states!",
+            StateHistogram::<C>::MAX_STATES
+        );
+        let mut counts = [0; HISTOGRAM_STATES];
+        self.iter().for_each(|cell| counts[cell.state_index()] += 1);
+        StateHistogram {
+            counts,
+            cell: PhantomData,
+        }
+    }
+}
+
+/// The length of the counts of a StateHistogram, which are kept in an array to avoid allocating
+const HISTOGRAM_STATES: usize = 256;
+
+/// The number of neighbors in each state of a FiniteCell, see `Neighborhood::histogram`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateHistogram<C: FiniteCell> {
+    // Indexed by the state index, the counts of states beyond STATE_COUNT stay 0
+    counts: [u32; HISTOGRAM_STATES],
+    cell: PhantomData<C>,
+}
+
+impl<C: FiniteCell> StateHistogram<C> {
+    /// The most states a cell may have to be counted in a histogram
+    pub const MAX_STATES: usize = HISTOGRAM_STATES;
+    /// Get the number of neighbors in the given state in O(1)
+    pub fn count(&self, state: &C) -> usize {
+        self.counts[state.state_index()] as usize
+    }
+    /// Get the number of neighbors in each state, ordered by the state index
+    pub fn counts(&self) -> &[u32] {
+        &self.counts[..C::STATE_COUNT]
+    }
+}
+
+impl<'a, C: Cell> Index<Direction> for Neighborhood<'a, C> {
+    type Output = C;
+    /// Panics if the board has no neighbor in the given direction
+    fn index(&self, direction: Direction) -> &Self::Output {
+        self.get(direction)
+            .expect("There is no neighbor in this direction on this topology!")
+    }
+}
+
+impl<'a, C: Cell> IntoIterator for Neighborhood<'a, C> {
+    type Item = &'a C;
+    type IntoIter = NeighborhoodIterator<'a, C>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,131 @@
+use std::ops::Range;
+
+/// Marks which square tiles of a board changed.
+///
+/// The board is divided into tiles of `tile_size` x `tile_size` cells, starting at the top left corner.
+/// The tiles in the last column and row are cut off at the edge of the board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirtyTiles {
+    width: usize,
+    height: usize,
+    tile_size: usize,
+    tiles_x: usize,
+    tiles_y: usize,
+    dirty: Vec<bool>,
+}
+
+impl DirtyTiles {
+    /// Construct the tiles of a board of the given dimension, with every tile marked dirty.
+    pub fn new(width: usize, height: usize, tile_size: usize) -> Self {
+        assert!(tile_size > 0, "Tiles cannot be empty!");
+        let (tiles_x, tiles_y) = (width.div_ceil(tile_size), height.div_ceil(tile_size));
+        Self {
+            width,
+            height,
+            tile_size,
+            tiles_x,
+            tiles_y,
+            dirty: vec![true; tiles_x * tiles_y],
+        }
+    }
+    pub fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+    /// Get the number of tiles in each row and column
+    pub fn dim(&self) -> (usize, usize) {
+        (self.tiles_x, self.tiles_y)
+    }
+    /// Whether the tile in the given column and row is marked dirty
+    pub fn is_dirty(&self, tx: usize, ty: usize) -> bool {
+        self.dirty[ty * self.tiles_x + tx]
+    }
+    /// Get the number of dirty tiles
+    pub fn count(&self) -> usize {
+        self.dirty.iter().filter(|&&dirty| dirty).count()
+    }
+    /// Iterate over the column and row of every dirty tile, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let tiles_x = self.tiles_x;
+        self.dirty
+            .iter()
+            .enumerate()
+            .filter(|(_, &dirty)| dirty)
+            .map(move |(i, _)| (i % tiles_x, i / tiles_x))
+    }
+    /// Get the range of columns and rows of the cells the tile is made of
+    pub fn cells(&self, tx: usize, ty: usize) -> (Range<usize>, Range<usize>) {
+        let (x0, y0) = (tx * self.tile_size, ty * self.tile_size);
+        (
+            x0..(x0 + self.tile_size).min(self.width),
+            y0..(y0 + self.tile_size).min(self.height),
+        )
+    }
+    /// Mark the tile containing the cell at the given coordinate dirty
+    pub fn mark_cell(&mut self, x: usize, y: usize) {
+        let (tx, ty) = (x / self.tile_size, y / self.tile_size);
+        self.dirty[ty * self.tiles_x + tx] = true;
+    }
+    /// Mark every tile dirty
+    pub fn mark_all(&mut self) {
+        self.dirty.iter_mut().for_each(|dirty| *dirty = true);
+    }
+    /// Mark every tile clean
+    pub fn clear(&mut self) {
+        self.dirty.iter_mut().for_each(|dirty| *dirty = false);
+    }
+    /// Mark every tile dirty which is dirty in the other set of tiles of the same board
+    pub fn merge(&mut self, other: &DirtyTiles) {
+        debug_assert_eq!(self.dim(), other.dim());
+        self.dirty
+            .iter_mut()
+            .zip(&other.dirty)
+            .for_each(|(dirty, &other)| *dirty |= other);
+    }
+    /// Replace the marks with one flag per tile, row by row
+    pub(crate) fn set_flags(&mut self, flags: Vec<bool>) {
+        debug_assert_eq!(flags.len(), self.dirty.len());
+        self.dirty = flags;
+    }
+    /// Get the tiles which have to be recomputed if the cells in the dirty tiles changed, one flag per tile, row by row.
+    /// These are the dirty tiles and every tile next to one. If the edges of the board depend on the cells
+    /// at the opposite edges, every tile within the radius of an edge is included as soon as one of them is dirty.
+    pub(crate) fn affected(&self, radius: usize, local_edges: bool) -> Vec<bool> {
+        let (tiles_x, tiles_y) = (self.tiles_x as isize, self.tiles_y as isize);
+        let mut affected = vec![false; self.dirty.len()];
+        for (tx, ty) in self.iter() {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (x, y) = (tx as isize + dx, ty as isize + dy);
+                    if (0..tiles_x).contains(&x) && (0..tiles_y).contains(&y) {
+                        affected[(y * tiles_x + x) as usize] = true;
+                    }
+                }
+            }
+        }
+        if !local_edges {
+            let near_edge: Vec<bool> = (0..self.dirty.len())
+                .map(|i| self.near_edge(i % self.tiles_x, i / self.tiles_x, radius))
+                .collect();
+            let edge_dirty = self
+                .dirty
+                .iter()
+                .zip(&near_edge)
+                .any(|(&dirty, &near)| dirty && near);
+            if edge_dirty {
+                affected
+                    .iter_mut()
+                    .zip(&near_edge)
+                    .for_each(|(affected, &near)| *affected |= near);
+            }
+        }
+        affected
+    }
+    /// Whether the tile contains cells within the radius of an edge of the board
+    fn near_edge(&self, tx: usize, ty: usize, radius: usize) -> bool {
+        let (xs, ys) = self.cells(tx, ty);
+        xs.start < radius
+            || xs.end + radius > self.width
+            || ys.start < radius
+            || ys.end + radius > self.height
+    }
+}
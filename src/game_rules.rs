@@ -1,11 +1,20 @@
+use rand::{Error, RngCore};
+use std::fmt;
+
 use crate::cell::*;
 use crate::game_board::iter::*;
 use crate::game_board::*;
 
 pub trait GameRule: Copy + Default {
     type Cell: Cell;
-    /// The implementation of this function should apply the game rule that is represented with this struct for cell based on all eight neigboring cells.
-    fn apply(cell: &Self::Cell, neighbor_iter: NeighborhoodIterator<Self::Cell>) -> Self::Cell;
+    /// The implementation of this function should apply the game rule that is represented with this struct for cell based on all neigboring cells.
+    /// The neighbors can be looked up by their Direction, or iterated in the order given by `NeighborhoodShape::offsets`
+    /// for the neighborhood of the rule.
+    fn apply(cell: &Self::Cell, neighbors: Neighborhood<Self::Cell>) -> Self::Cell;
+    /// The shape of the neighborhood the rule is applied to. Defaults to the eight surrounding cells.
+    fn neighborhood() -> NeighborhoodShape {
+        NeighborhoodShape::default()
+    }
     fn iter<'a>(&self, board: &'a GameBoard<Self::Cell>) -> GameRuleIter<'a, Self> {
         GameRuleIter {
             rule: Self::apply,
@@ -17,8 +26,8 @@ pub trait GameRule: Copy + Default {
     }
 }
 
-pub struct GameRuleIter<'a, Rule: GameRule + ?Sized> {
-    rule: fn(&Rule::Cell, NeighborhoodIterator<Rule::Cell>) -> Rule::Cell,
+pub struct GameRuleIter<'a, Rule: GameRule> {
+    rule: fn(&Rule::Cell, Neighborhood<Rule::Cell>) -> Rule::Cell,
     local_iter: LocalGroupIterator<'a, Rule::Cell>,
     // board: &'a GameBoard<Rule::Cell>,
     // x: usize,
@@ -35,3 +44,209 @@ where
         Some((self.rule)(c, n))
     }
 }
+
+/// A game rule which may draw random numbers to decide the next state of a cell.
+///
+/// Every GameRule is a StochasticGameRule which ignores the random number generator.
+pub trait StochasticGameRule: Copy + Default {
+    type Cell: Cell;
+    /// Apply the rule to the cell based on all neighboring cells, like `GameRule::apply`.
+    /// Every random decision has to be drawn from the given random number generator, which is unique
+    /// to the cell and generation, so a game can be replayed from its seed.
+    fn apply_with_rng(
+        cell: &Self::Cell,
+        neighbors: Neighborhood<Self::Cell>,
+        rng: &mut CellRng,
+    ) -> Self::Cell;
+    /// The shape of the neighborhood the rule is applied to. Defaults to the eight surrounding cells.
+    fn neighborhood() -> NeighborhoodShape {
+        NeighborhoodShape::default()
+    }
+    /// Whether the rule always produces the same cell for the same neighborhood, without drawing random numbers.
+    /// Only such rules allow skipping the parts of a board which did not change.
+    fn is_deterministic() -> bool {
+        false
+    }
+}
+
+impl<R: GameRule> StochasticGameRule for R {
+    type Cell = R::Cell;
+    fn apply_with_rng(
+        cell: &Self::Cell,
+        neighbors: Neighborhood<Self::Cell>,
+        _rng: &mut CellRng,
+    ) -> Self::Cell {
+        R::apply(cell, neighbors)
+    }
+    fn neighborhood() -> NeighborhoodShape {
+        <R as GameRule>::neighborhood()
+    }
+    fn is_deterministic() -> bool {
+        true
+    }
+}
+
+/// A game rule which is applied through an instance of the rule, so it can hold parameters chosen at runtime,
+/// and which knows where and when the cell it is applied to is.
+///
+/// Every StochasticGameRule, and with it every GameRule, is a ContextGameRule ignoring the context.
+pub trait ContextGameRule: Clone + Default + Send + Sync {
+    type Cell: Cell;
+    /// Apply the rule to the cell based on all neighboring cells, like `GameRule::apply`,
+    /// and on the coordinate, generation and random number generator in the context.
+    fn apply_in_context(
+        &self,
+        cell: &Self::Cell,
+        neighbors: Neighborhood<Self::Cell>,
+        context: &mut RuleContext,
+    ) -> Self::Cell;
+    /// The shape of the neighborhood the rule is applied to. Defaults to the eight surrounding cells.
+    fn neighborhood(&self) -> NeighborhoodShape {
+        NeighborhoodShape::default()
+    }
+    /// Whether the rule always produces the same cell for the same neighborhood,
+    /// without looking at the coordinate, generation or random number generator.
+    /// Only such rules allow skipping the parts of a board which did not change.
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+    /// Construct the rule described by a rule string, such as `B36/S23` for life-like rules.
+    /// Rules which cannot be described by a string return `ParseRuleError::Unsupported`.
+    fn parse_rule(rule: &str) -> Result<Self, ParseRuleError> {
+        let _ = rule;
+        Err(ParseRuleError::Unsupported)
+    }
+}
+
+impl<R: StochasticGameRule + Send + Sync> ContextGameRule for R {
+    type Cell = R::Cell;
+    fn apply_in_context(
+        &self,
+        cell: &Self::Cell,
+        neighbors: Neighborhood<Self::Cell>,
+        context: &mut RuleContext,
+    ) -> Self::Cell {
+        R::apply_with_rng(cell, neighbors, &mut context.rng)
+    }
+    fn neighborhood(&self) -> NeighborhoodShape {
+        <R as StochasticGameRule>::neighborhood()
+    }
+    fn is_deterministic(&self) -> bool {
+        <R as StochasticGameRule>::is_deterministic()
+    }
+}
+
+/// The reasons a rule string cannot be turned into a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRuleError {
+    /// The rule cannot be described by a rule string
+    Unsupported,
+    /// The rule string does not consist of the expected number of parts separated by slashes
+    WrongNumberOfParts { expected: usize, found: usize },
+    /// The rule string contains a character which is not allowed at its position
+    InvalidCharacter(char),
+    /// The parts of the rule string are prefixed inconsistently, for example with the same letter twice
+    InvalidPrefixes,
+    /// The number of states of a multi-state rule is missing or not supported
+    InvalidNumberOfStates,
+    /// The rule turns on cells without alive neighbors, which the game cannot play
+    UnsupportedBirthOnZero,
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported => write!(f, "the rule cannot be described by a rule string"),
+            Self::WrongNumberOfParts { expected, found } => write!(
+                f,
+                "expected {} parts separated by '/', found {}",
+                expected, found
+            ),
+            Self::InvalidCharacter(c) => write!(f, "invalid character '{}'", c),
+            Self::InvalidPrefixes => write!(f, "the parts of the rule are prefixed inconsistently"),
+            Self::InvalidNumberOfStates => {
+                write!(f, "the number of states has to be between 2 and 255")
+            }
+            Self::UnsupportedBirthOnZero => {
+                write!(
+                    f,
+                    "rules with birth on 0 neighbors are not supported by this game"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseRuleError {}
+
+/// Where and when a ContextGameRule is applied
+#[derive(Debug, Clone)]
+pub struct RuleContext {
+    /// Column of the cell the rule is applied to
+    pub x: usize,
+    /// Row of the cell the rule is applied to
+    pub y: usize,
+    /// Number of steps the game took before this one
+    pub generation: u64,
+    /// Random number generator unique to the cell and generation
+    pub rng: CellRng,
+}
+
+impl RuleContext {
+    /// Construct the context of the cell at the given coordinate in the given generation of a game with the given seed
+    pub fn new(seed: u64, generation: u64, x: usize, y: usize) -> Self {
+        Self {
+            x,
+            y,
+            generation,
+            rng: CellRng::new(seed, generation, x, y),
+        }
+    }
+}
+
+/// The random number generator handed to a StochasticGameRule for a single cell in a single generation.
+///
+/// It is derived from the seed of the game, the generation and the coordinate of the cell, so the random numbers
+/// do not depend on the order in which the cells are computed or on the number of threads computing them.
+/// The numbers are generated with SplitMix64.
+#[derive(Debug, Clone)]
+pub struct CellRng {
+    state: u64,
+}
+
+impl CellRng {
+    pub fn new(seed: u64, generation: u64, x: usize, y: usize) -> Self {
+        let state = [generation, x as u64, y as u64]
+            .iter()
+            .fold(mix(seed), |state, &value| mix(state ^ value));
+        Self { state }
+    }
+}
+
+impl RngCore for CellRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The output function of SplitMix64, which scrambles the bits of its input
+fn mix(z: u64) -> u64 {
+    let z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    let z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::cell::*;
+use crate::game_board::*;
+use crate::game_rules::*;
+use crate::life_like::{parse_counts, split_prefix};
+use crate::rgba::RGBA;
+
+/// A cell of a Generations rule. It is dead, alive, or dying, passing through the refractory states
+/// 2 up to `states - 1` before it is dead again. Only alive cells count as alive neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenerationsCell {
+    state: u8,
+    states: u8,
+}
+
+impl GenerationsCell {
+    /// Construct a cell in the given state of a rule with the given number of states.
+    /// Panics if there are fewer than 2 states or the state is not below the number of states.
+    pub fn new(state: u8, states: u8) -> Self {
+        assert!(states >= 2, "A Generations cell needs at least 2 states!");
+        assert!(
+            state < states,
+            "The state of a cell has to be below the number of states!"
+        );
+        Self { state, states }
+    }
+    pub fn dead(states: u8) -> Self {
+        Self::new(0, states)
+    }
+    pub fn alive(states: u8) -> Self {
+        Self::new(1, states)
+    }
+    /// Get the state, 0 for dead, 1 for alive, and above for dying
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+    /// Get the number of states of the rule the cell belongs to
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+    pub fn is_alive(&self) -> bool {
+        self.state == 1
+    }
+    pub fn is_dying(&self) -> bool {
+        self.state >= 2
+    }
+}
+
+impl Cell for GenerationsCell {
+    /// Dead cells are black and alive cells white. Dying cells fade from orange to dark red as they age.
+    fn to_rgba(&self) -> RGBA {
+        match self.state {
+            0 => RGBA::black(),
+            1 => RGBA::white(),
+            state => {
+                let (young, old) = ([0xFF, 0xA0, 0x00], [0x40, 0x00, 0x00]);
+                let dying_states = self.states - 2;
+                let age = (state - 2) as f32 / (dying_states.max(2) - 1) as f32;
+                let mut rgba = [0xFF; 4];
+                for (channel, (&young, &old)) in rgba.iter_mut().zip(young.iter().zip(old.iter())) {
+                    *channel = (young as f32 * (1.0 - age) + old as f32 * age) as u8;
+                }
+                RGBA(rgba)
+            }
+        }
+    }
+    fn next(&self) -> Self {
+        Self::new((self.state + 1) % self.states, self.states)
+    }
+}
+
+/// A Generations rule, like a life-like rule whose cells do not die at once but pass through refractory states.
+/// Dead cells are born and alive cells survive depending on their number of alive neighbors in the Moore neighborhood.
+/// An alive cell which does not survive and every dying cell advance to the next state.
+///
+/// The rule is chosen at runtime, so it is a ContextGameRule rather than a GameRule.
+/// Its rule string has the form `S/B/C` like `345/2/4` for Star Wars, or `B2/S345/C4` with prefixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenerationsRule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+    states: u8,
+}
+
+impl Default for GenerationsRule {
+    fn default() -> Self {
+        Self::brians_brain()
+    }
+}
+
+impl GenerationsRule {
+    /// Construct a rule from the neighbor counts leading to birth and survival, and the total number of states.
+    /// Counts above 8 are ignored. Panics if there are fewer than 2 states.
+    pub fn new(birth: &[usize], survival: &[usize], states: u8) -> Self {
+        assert!(states >= 2, "A Generations rule needs at least 2 states!");
+        let mut rule = Self {
+            birth: [false; 9],
+            survival: [false; 9],
+            states,
+        };
+        birth
+            .iter()
+            .filter(|&&n| n <= 8)
+            .for_each(|&n| rule.birth[n] = true);
+        survival
+            .iter()
+            .filter(|&&n| n <= 8)
+            .for_each(|&n| rule.survival[n] = true);
+        rule
+    }
+    /// Brian's Brain, /2/3
+    pub fn brians_brain() -> Self {
+        Self::new(&[2], &[], 3)
+    }
+    /// Star Wars, 345/2/4
+    pub fn star_wars() -> Self {
+        Self::new(&[2], &[3, 4, 5], 4)
+    }
+    /// Get the number of states including dead and alive
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+    /// The dead cell of this rule
+    pub fn dead(&self) -> GenerationsCell {
+        GenerationsCell::dead(self.states)
+    }
+    /// The alive cell of this rule
+    pub fn alive(&self) -> GenerationsCell {
+        GenerationsCell::alive(self.states)
+    }
+    /// The state following the given one, given its number of alive neighbors
+    pub fn next_state(&self, state: u8, alive_neighbors: usize) -> u8 {
+        match state {
+            0 if self.birth[alive_neighbors] => 1,
+            0 => 0,
+            1 if self.survival[alive_neighbors] => 1,
+            // Cells of a rule with a different number of states die once they run out of this rule's states
+            state if state + 1 < self.states => state + 1,
+            _ => 0,
+        }
+    }
+}
+
+impl ContextGameRule for GenerationsRule {
+    type Cell = GenerationsCell;
+    fn apply_in_context(
+        &self,
+        cell: &Self::Cell,
+        neighbors: Neighborhood<Self::Cell>,
+        _context: &mut RuleContext,
+    ) -> Self::Cell {
+        let alive_neighbors = neighbors.count_where(GenerationsCell::is_alive);
+        GenerationsCell::new(self.next_state(cell.state, alive_neighbors), self.states)
+    }
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+    fn parse_rule(rule: &str) -> Result<Self, ParseRuleError> {
+        rule.parse()
+    }
+}
+
+/// Writes the rule in the form `345/2/4`
+impl fmt::Display for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |set: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| set[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(
+            f,
+            "{}/{}/{}",
+            counts(&self.survival),
+            counts(&self.birth),
+            self.states
+        )
+    }
+}
+
+/// Parses Golly-style rule strings, either without prefixes in the survival/birth/states order like `345/2/4`,
+/// or with the prefixes B, S and C in any order like `B2/S345/C4`. Letters are case-insensitive.
+impl FromStr for GenerationsRule {
+    type Err = ParseRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('/').collect();
+        if parts.len() != 3 {
+            return Err(ParseRuleError::WrongNumberOfParts {
+                expected: 3,
+                found: parts.len(),
+            });
+        }
+        let mut prefixed = [None; 3];
+        let mut unprefixed = [""; 3];
+        for (i, part) in parts.iter().enumerate() {
+            match split_prefix(part, &['S', 'B', 'C'])? {
+                (Some(letter), rest) => {
+                    let slot = &mut prefixed["SBC".find(letter).unwrap()];
+                    if slot.replace(rest).is_some() {
+                        return Err(ParseRuleError::InvalidPrefixes);
+                    }
+                }
+                (None, rest) => unprefixed[i] = rest,
+            }
+        }
+        let [survival, birth, states] = match prefixed {
+            [None, None, None] => unprefixed,
+            [Some(survival), Some(birth), Some(states)] => [survival, birth, states],
+            _ => return Err(ParseRuleError::InvalidPrefixes),
+        };
+        let states = match states.parse::<u8>() {
+            Ok(states) if states >= 2 => states,
+            _ => return Err(ParseRuleError::InvalidNumberOfStates),
+        };
+        Ok(Self::new(
+            &parse_counts(birth, 8)?,
+            &parse_counts(survival, 8)?,
+            states,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{traits::CellGame as CellGameTrait, CellGame};
+
+    #[test]
+    fn test_parse_generations_rules() {
+        for s in ["345/2/4", "B2/S345/C4", "c4/s345/b2"] {
+            assert_eq!(s.parse(), Ok(GenerationsRule::star_wars()));
+        }
+        assert_eq!("/2/3".parse(), Ok(GenerationsRule::brians_brain()));
+        assert_eq!(GenerationsRule::star_wars().to_string(), "345/2/4");
+        assert_eq!(
+            "/2/1".parse::<GenerationsRule>(),
+            Err(ParseRuleError::InvalidNumberOfStates)
+        );
+        assert_eq!(
+            "B2/345/C4".parse::<GenerationsRule>(),
+            Err(ParseRuleError::InvalidPrefixes)
+        );
+    }
+
+    #[test]
+    fn test_brians_brain_dies_through_refractory_state() {
+        let rule = GenerationsRule::brians_brain();
+        let mut board = GameBoard::new(5, 5, rule.dead());
+        *board.get_mut(1, 2).unwrap() = rule.alive();
+        *board.get_mut(2, 2).unwrap() = rule.alive();
+        let mut game = CellGame::with_rule(board, rule);
+        game.step();
+        let state =
+            |game: &CellGame<_, _>, x, y| game.get_board().get(x, y).map(GenerationsCell::state);
+        // Alive cells never survive in Brian's Brain, and the cells seeing both of them are born
+        assert_eq!(state(&game, 1, 2), Some(2));
+        assert_eq!(state(&game, 2, 2), Some(2));
+        assert_eq!(state(&game, 1, 1), Some(1));
+        assert_eq!(state(&game, 2, 3), Some(1));
+        assert_eq!(state(&game, 0, 2), Some(0));
+        game.step();
+        assert_eq!(state(&game, 1, 2), Some(0));
+    }
+}
@@ -0,0 +1,624 @@
+use std::collections::HashMap;
+
+use crate::cell::*;
+use crate::game::traits;
+use crate::game_board::*;
+use crate::game_rules::ParseRuleError;
+use crate::life_like::{LifeLike, LifeLikeRule};
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+/// Once the node store grows beyond this many nodes, all nodes not reachable from the root are dropped.
+const MAX_NODES: usize = 1 << 22;
+/// The root never grows beyond this level, so coordinates relative to it fit into an u64.
+pub(crate) const MAX_LEVEL: u8 = 62;
+
+/// A square of 2^level x 2^level cells, made of four squares of the level below.
+/// Level 0 nodes are single cells.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    children: [NodeId; 4], // nw, ne, sw, se
+    level: u8,
+    population: u64,
+}
+
+/// A node of the quadtree as listed in a macrocell file.
+/// Children refer to earlier nodes of the list by their index plus one, or are 0 for an empty node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MacrocellNode {
+    /// A square of 8x8 cells, one byte per row with the leftmost cell in the lowest bit
+    Leaf([u8; 8]),
+    /// A square of 2^level x 2^level cells with the children nw, ne, sw and se
+    Node { level: u8, children: [usize; 4] },
+}
+
+/// Canonical quadtree nodes together with the memoized results of stepping them.
+#[derive(Debug, Clone)]
+struct NodeStore {
+    nodes: Vec<Node>,
+    interned: HashMap<[NodeId; 4], NodeId>,
+    // The empty node of every level, indexed by level
+    empty: Vec<NodeId>,
+    // The center of a node advanced by 2^j generations, keyed by (node, j)
+    results: HashMap<(NodeId, u8), NodeId>,
+    rule: LifeLikeRule,
+}
+
+impl NodeStore {
+    fn new(rule: LifeLikeRule) -> Self {
+        let leaf = |population| Node {
+            children: [DEAD; 4],
+            level: 0,
+            population,
+        };
+        Self {
+            nodes: vec![leaf(0), leaf(1)],
+            interned: HashMap::new(),
+            empty: vec![DEAD],
+            results: HashMap::new(),
+            rule,
+        }
+    }
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+    fn children(&self, id: NodeId) -> [NodeId; 4] {
+        self.nodes[id as usize].children
+    }
+    fn level(&self, id: NodeId) -> u8 {
+        self.nodes[id as usize].level
+    }
+    fn population(&self, id: NodeId) -> u64 {
+        self.nodes[id as usize].population
+    }
+    /// Get the canonical node with the given children, which all have to be of the same level.
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.interned.get(&children) {
+            return id;
+        }
+        let level = self.level(children[0]) + 1;
+        let population = children.iter().fold(0u64, |sum, &child| {
+            sum.saturating_add(self.population(child))
+        });
+        let id = NodeId::try_from(self.nodes.len()).expect("HashLife node store overflowed!");
+        self.nodes.push(Node {
+            children,
+            level,
+            population,
+        });
+        self.interned.insert(children, id);
+        id
+    }
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let below = *self.empty.last().expect("The empty leaf always exists");
+            let empty = self.join([below; 4]);
+            self.empty.push(empty);
+        }
+        self.empty[level as usize]
+    }
+    /// Surround the node with empty space, producing a node of the next level with the given node at its center.
+    fn expand(&mut self, id: NodeId) -> NodeId {
+        let level = self.level(id);
+        assert!(level < MAX_LEVEL, "The HashLife pattern grew too large!");
+        let [nw, ne, sw, se] = self.children(id);
+        let e = self.empty(level - 1);
+        let nw = self.join([e, e, e, nw]);
+        let ne = self.join([e, e, ne, e]);
+        let sw = self.join([e, sw, e, e]);
+        let se = self.join([se, e, e, e]);
+        self.join([nw, ne, sw, se])
+    }
+    /// Get the population of the center square with a quarter of the width of the node
+    fn inner_population(&self, id: NodeId) -> u64 {
+        let [nw, ne, sw, se] = self.children(id);
+        let inner = [
+            self.children(self.children(nw)[3])[3],
+            self.children(self.children(ne)[2])[2],
+            self.children(self.children(sw)[1])[1],
+            self.children(self.children(se)[0])[0],
+        ];
+        inner.iter().map(|&id| self.population(id)).sum()
+    }
+    /// Get the center of the node, which is a node of the level below, advanced by 2^j generations.
+    /// j is limited to level - 2, which is as far as the content of the node determines its center.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let node = self.node(id);
+        let j = j.min(node.level - 2);
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+        let result = if node.population == 0 {
+            self.empty(node.level - 1)
+        } else if node.level == 2 {
+            self.step_4x4(id)
+        } else {
+            let [nw, ne, sw, se] = node.children;
+            let [_, nw_ne, nw_sw, nw_se] = self.children(nw);
+            let [ne_nw, _, ne_sw, ne_se] = self.children(ne);
+            let [sw_nw, sw_ne, _, sw_se] = self.children(sw);
+            let [se_nw, se_ne, se_sw, _] = self.children(se);
+            // Nine overlapping nodes of the level below, covering the node in a 3x3 grid
+            let n01 = self.join([nw_ne, ne_nw, nw_se, ne_sw]);
+            let n10 = self.join([nw_sw, nw_se, sw_nw, sw_ne]);
+            let n11 = self.join([nw_se, ne_sw, sw_ne, se_nw]);
+            let n12 = self.join([ne_sw, ne_se, se_nw, se_ne]);
+            let n21 = self.join([sw_ne, se_nw, sw_se, se_sw]);
+            let c = [nw, n01, ne, n10, n11, n12, sw, n21, se].map(|n| self.successor(n, j));
+            let quadrants = [[0, 1, 3, 4], [1, 2, 4, 5], [3, 4, 6, 7], [4, 5, 7, 8]];
+            let children = if j < node.level - 2 {
+                // The nine results are already advanced far enough, only their centers are joined
+                quadrants.map(|[a, b, d, e]| {
+                    let centers = [
+                        self.children(c[a])[3],
+                        self.children(c[b])[2],
+                        self.children(c[d])[1],
+                        self.children(c[e])[0],
+                    ];
+                    self.join(centers)
+                })
+            } else {
+                // The nine results are advanced halfway, so advance their joins by the other half
+                quadrants.map(|[a, b, d, e]| {
+                    let joined = self.join([c[a], c[b], c[d], c[e]]);
+                    self.successor(joined, j)
+                })
+            };
+            self.join(children)
+        };
+        self.results.insert((id, j), result);
+        result
+    }
+    /// Advance the center 2x2 cells of a 4x4 node by one generation
+    fn step_4x4(&mut self, id: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        for (i, child) in self.children(id).iter().enumerate() {
+            for (j, leaf) in self.children(*child).iter().enumerate() {
+                grid[(i / 2) * 2 + j / 2][(i % 2) * 2 + j % 2] = *leaf == ALIVE;
+            }
+        }
+        let next = |x: usize, y: usize| {
+            let alive_neighbors = (y - 1..=y + 1)
+                .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && grid[ny][nx])
+                .count();
+            if self.rule.next_alive(grid[y][x], alive_neighbors) {
+                ALIVE
+            } else {
+                DEAD
+            }
+        };
+        let children = [next(1, 1), next(2, 1), next(1, 2), next(2, 2)];
+        self.join(children)
+    }
+    /// Get the node with the cell at (x, y) relative to the top left corner of the node replaced
+    fn with_cell(&mut self, id: NodeId, x: u64, y: u64, alive: bool) -> NodeId {
+        let level = self.level(id);
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+        let mut children = self.children(id);
+        children[quadrant] = self.with_cell(children[quadrant], x % half, y % half, alive);
+        self.join(children)
+    }
+    fn cell(&self, id: NodeId, x: u64, y: u64) -> bool {
+        let node = self.node(id);
+        if node.population == 0 || node.level == 0 {
+            return node.population != 0;
+        }
+        let half = 1 << (node.level - 1);
+        let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+        self.cell(node.children[quadrant], x % half, y % half)
+    }
+    /// Call the function with the coordinate of every alive cell of the node inside the given region.
+    /// The node has its top left corner at (x, y), the region is given as (min_x, min_y, max_x, max_y) with the maximum excluded.
+    fn for_each_alive(
+        &self,
+        id: NodeId,
+        (x, y): (i64, i64),
+        region: (i64, i64, i64, i64),
+        f: &mut impl FnMut(i64, i64),
+    ) {
+        let node = self.node(id);
+        let size = 1i64 << node.level;
+        let (min_x, min_y, max_x, max_y) = region;
+        if node.population == 0
+            || x >= max_x
+            || y >= max_y
+            || x + size <= min_x
+            || y + size <= min_y
+        {
+            return;
+        }
+        if node.level == 0 {
+            f(x, y);
+            return;
+        }
+        let half = size / 2;
+        for (i, &child) in node.children.iter().enumerate() {
+            let offset = ((i % 2) as i64 * half, (i / 2) as i64 * half);
+            self.for_each_alive(child, (x + offset.0, y + offset.1), region, f);
+        }
+    }
+    /// Copy the region of the root with the top left cell at the given coordinate onto the GameBoard
+    fn write_board<C: BinaryCell>(&self, root: NodeId, board: &mut GameBoard<C>, x: i64, y: i64) {
+        let (width, height) = board.dim();
+        board.iter_mut().for_each(|cell| *cell = C::dead());
+        let half = 1i64 << (self.level(root) - 1);
+        let region = (x, y, x + width as i64, y + height as i64);
+        self.for_each_alive(root, (-half, -half), region, &mut |cell_x, cell_y| {
+            let (board_x, board_y) = ((cell_x - x) as usize, (cell_y - y) as usize);
+            *board
+                .get_mut(board_x, board_y)
+                .expect("Cell is inside the region") = C::alive();
+        });
+        board.update_border();
+    }
+    /// Get the node of the given level with the cell at (x, y) relative to the top left corner alive if the function says so
+    fn build_square(
+        &mut self,
+        level: u8,
+        x: u64,
+        y: u64,
+        alive: &impl Fn(u64, u64) -> bool,
+    ) -> NodeId {
+        if level == 0 {
+            return if alive(x, y) { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let children = [(0, 0), (half, 0), (0, half), (half, half)]
+            .map(|(dx, dy)| self.build_square(level - 1, x + dx, y + dy, alive));
+        self.join(children)
+    }
+    /// Append the node and all non-empty nodes below it to the macrocell nodes, each only once.
+    /// Returns the index plus one of the node in the list, or 0 if it is empty.
+    fn to_macrocell(
+        &self,
+        id: NodeId,
+        nodes: &mut Vec<MacrocellNode>,
+        indices: &mut HashMap<NodeId, usize>,
+    ) -> usize {
+        let node = self.node(id);
+        if node.population == 0 {
+            return 0;
+        }
+        if let Some(&index) = indices.get(&id) {
+            return index;
+        }
+        let entry = if node.level == 3 {
+            let row = |y| (0..8).fold(0, |row, x| row | (self.cell(id, x, y) as u8) << x);
+            MacrocellNode::Leaf([0, 1, 2, 3, 4, 5, 6, 7].map(row))
+        } else {
+            MacrocellNode::Node {
+                level: node.level,
+                children: node
+                    .children
+                    .map(|child| self.to_macrocell(child, nodes, indices)),
+            }
+        };
+        nodes.push(entry);
+        indices.insert(id, nodes.len());
+        nodes.len()
+    }
+    /// Copy the node and everything below it from another store into this one
+    fn copy_from(
+        &mut self,
+        other: &Self,
+        id: NodeId,
+        copied: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if id == DEAD || id == ALIVE {
+            return id;
+        }
+        if let Some(&copy) = copied.get(&id) {
+            return copy;
+        }
+        let children = other
+            .children(id)
+            .map(|child| self.copy_from(other, child, copied));
+        let copy = self.join(children);
+        copied.insert(id, copy);
+        copy
+    }
+}
+
+/// A game of a life-like rule on an unbounded board, stepped with the HashLife algorithm.
+///
+/// The board is stored as a quadtree of canonical nodes, and the result of advancing any node is memoized.
+/// This makes it possible to advance highly regular patterns by huge numbers of generations at once.
+/// Only a rectangular viewport of the board is visible through `get_board`.
+pub struct HashLifeGame<C: BinaryCell> {
+    store: NodeStore,
+    // The root covers the coordinates -2^(level - 1)..2^(level - 1) on both axes
+    root: NodeId,
+    generation: u64,
+    step_exponent: u8,
+    view: GameBoard<C>,
+    origin: (i64, i64),
+}
+
+/// Check that HashLife can play the rule. Rules with birth on 0 neighbors (B0) would turn on the empty space
+/// around the pattern, which the unbounded universe of empty nodes cannot represent.
+pub fn check_rule(rule: LifeLikeRule) -> Result<LifeLikeRule, ParseRuleError> {
+    if rule.birth(0) {
+        return Err(ParseRuleError::UnsupportedBirthOnZero);
+    }
+    Ok(rule)
+}
+
+impl<C: BinaryCell> HashLifeGame<C> {
+    /// Construct an empty game of the given rule with a viewport of the given size.
+    /// Panics if the rule is not supported, see `check_rule`.
+    pub fn with_rule(width: usize, height: usize, rule: LifeLikeRule) -> Self {
+        let rule = check_rule(rule).expect("HashLife cannot play the rule");
+        let mut store = NodeStore::new(rule);
+        let root = store.empty(3);
+        Self {
+            store,
+            root,
+            generation: 0,
+            step_exponent: 0,
+            view: GameBoard::new(width, height, C::dead()),
+            origin: (0, 0),
+        }
+    }
+    /// Get the rule of the game
+    pub fn rule(&self) -> LifeLikeRule {
+        self.store.rule
+    }
+    /// Replace the rule of the game. This discards all memoized results.
+    /// Panics if the rule is not supported, see `check_rule`.
+    pub fn set_rule(&mut self, rule: LifeLikeRule) {
+        self.store.rule = check_rule(rule).expect("HashLife cannot play the rule");
+        self.store.results.clear();
+    }
+    /// Get the number of alive cells
+    pub fn population(&self) -> u64 {
+        self.store.population(self.root)
+    }
+    /// Get the exponent of the number of generations a single `step` advances the game by
+    pub fn step_exponent(&self) -> u8 {
+        self.step_exponent
+    }
+    /// Make a single `step` advance the game by 2^k generations
+    pub fn set_step_exponent(&mut self, k: u8) {
+        self.step_exponent = k;
+    }
+    /// Advance the game by 2^k generations at once
+    pub fn step_pow2(&mut self, k: u8) {
+        assert!(
+            k + 3 <= MAX_LEVEL,
+            "Cannot advance by more than 2^59 generations at once!"
+        );
+        // The root has to be large enough to advance 2^k generations, and the pattern has to sit
+        // in its central quarter, so it cannot grow beyond the center of the root in the meantime.
+        while self.store.level(self.root) < k + 3
+            || self.store.inner_population(self.root) != self.population()
+        {
+            self.root = self.store.expand(self.root);
+        }
+        self.root = self.store.successor(self.root, k);
+        self.generation = self.generation.wrapping_add(1 << k);
+        if self.store.nodes.len() > MAX_NODES {
+            self.collect_garbage();
+        }
+    }
+    /// Whether the cell at the given coordinate is alive
+    pub fn get_cell(&self, x: i64, y: i64) -> bool {
+        let half = 1i64 << (self.store.level(self.root) - 1);
+        if x < -half || x >= half || y < -half || y >= half {
+            return false;
+        }
+        self.store
+            .cell(self.root, (x + half) as u64, (y + half) as u64)
+    }
+    /// Set whether the cell at the given coordinate is alive, growing the board if necessary
+    pub fn set_cell(&mut self, x: i64, y: i64, alive: bool) {
+        loop {
+            let half = 1i64 << (self.store.level(self.root) - 1);
+            if (-half..half).contains(&x) && (-half..half).contains(&y) {
+                let (x, y) = ((x + half) as u64, (y + half) as u64);
+                self.root = self.store.with_cell(self.root, x, y, alive);
+                return;
+            }
+            if !alive {
+                return;
+            }
+            self.root = self.store.expand(self.root);
+        }
+    }
+    /// Copy the cells of the GameBoard onto the game, with the top left cell of the board at the given coordinate
+    pub fn read_board(&mut self, board: &GameBoard<C>, x: i64, y: i64) {
+        let (width, _) = board.dim();
+        for (i, cell) in board.iter().enumerate() {
+            let (x, y) = (x + (i % width) as i64, y + (i / width) as i64);
+            if cell.is_alive() || self.get_cell(x, y) {
+                self.set_cell(x, y, cell.is_alive());
+            }
+        }
+    }
+    /// Copy the region of the game with the top left cell at the given coordinate onto the GameBoard
+    pub fn write_board(&self, board: &mut GameBoard<C>, x: i64, y: i64) {
+        self.store.write_board(self.root, board, x, y);
+    }
+    /// Construct a GameBoard with a dead border from the region of the game with the top left cell at the given coordinate
+    pub fn to_board(&self, x: i64, y: i64, width: usize, height: usize) -> GameBoard<C> {
+        let mut board = GameBoard::new(width, height, C::dead());
+        self.write_board(&mut board, x, y);
+        board
+    }
+    /// Get the coordinate of the top left cell of the viewport
+    pub fn origin(&self) -> (i64, i64) {
+        self.origin
+    }
+    /// Move the viewport so its top left cell is at the given coordinate
+    pub fn set_origin(&mut self, origin: (i64, i64)) {
+        self.origin = origin;
+    }
+    /// Replace the cells of the game by the quadtree of a macrocell file, whose last node is the root.
+    /// The center of the root is placed at the origin. The nodes have to be ordered and of matching levels.
+    pub(crate) fn set_macrocell(&mut self, nodes: &[MacrocellNode]) {
+        let mut ids: Vec<NodeId> = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let id = match node {
+                MacrocellNode::Leaf(rows) => self
+                    .store
+                    .build_square(3, 0, 0, &|x, y| rows[y as usize] >> x & 1 == 1),
+                MacrocellNode::Node { level, children } => {
+                    let children = children.map(|child| match child {
+                        0 => self.store.empty(level - 1),
+                        child => ids[child - 1],
+                    });
+                    self.store.join(children)
+                }
+            };
+            ids.push(id);
+        }
+        self.root = match ids.last() {
+            Some(&root) => root,
+            None => self.store.empty(3),
+        };
+        self.generation = 0;
+    }
+    /// Get the quadtree of the game as listed in a macrocell file, with the root last.
+    /// Empty nodes are left out, so the list is empty if no cell is alive.
+    pub(crate) fn macrocell(&self) -> Vec<MacrocellNode> {
+        let mut nodes = Vec::new();
+        self.store
+            .to_macrocell(self.root, &mut nodes, &mut HashMap::new());
+        nodes
+    }
+    /// Drop all nodes and memoized results which are not needed for the current pattern
+    fn collect_garbage(&mut self) {
+        let mut store = NodeStore::new(self.store.rule);
+        self.root = store.copy_from(&self.store, self.root, &mut HashMap::new());
+        self.store = store;
+    }
+    fn fill_viewport(&mut self) {
+        let (width, height) = self.view.dim();
+        let (x0, y0) = self.origin;
+        for y in y0..y0 + height as i64 {
+            for x in x0..x0 + width as i64 {
+                self.set_cell(x, y, true);
+            }
+        }
+    }
+}
+
+impl<C: BinaryCell> traits::CellGame for HashLifeGame<C> {
+    type Cell = C;
+
+    type GameRule = LifeLike<C>;
+
+    fn get_board(&self) -> &GameBoard<Self::Cell> {
+        &self.view
+    }
+    fn update_board(&mut self) {
+        let (x, y) = self.origin;
+        self.store.write_board(self.root, &mut self.view, x, y);
+    }
+    fn set_rule_str(&mut self, rule: &str) -> Result<(), ParseRuleError> {
+        self.set_rule(check_rule(rule.parse()?)?);
+        Ok(())
+    }
+    fn step(&mut self) {
+        self.step_pow2(self.step_exponent);
+    }
+    /// Get the number of generations the game was advanced by, which grows by 2^k with each step
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+    /// Kill every cell. Since the board is unbounded, clearing with an alive cell only fills the viewport.
+    fn clear(&mut self, clear_cell: Self::Cell) {
+        self.root = self.store.empty(3);
+        if clear_cell.is_alive() {
+            self.fill_viewport();
+        }
+    }
+    fn dim(&self) -> (usize, usize) {
+        self.view.dim()
+    }
+    fn next_cell(&mut self, x: usize, y: usize) {
+        let (x0, y0) = self.origin;
+        let (x_game, y_game) = (x0 + x as i64, y0 + y as i64);
+        let next_cell = if self.get_cell(x_game, y_game) {
+            C::alive().next()
+        } else {
+            C::dead().next()
+        };
+        self.set_cell(x_game, y_game, next_cell.is_alive());
+        self.view.set(x, y, next_cell);
+    }
+    /// Construct a game of Conway's Game of Life with a viewport of the given size.
+    /// The board is unbounded, so the boundary only lines the viewport, and an alive cell only fills the viewport.
+    fn with_boundary(
+        width: usize,
+        height: usize,
+        cell: Self::Cell,
+        boundary: Boundary<Self::Cell>,
+    ) -> Self {
+        let mut game = Self::with_rule(width, height, LifeLikeRule::default());
+        game.view.set_boundary(boundary);
+        if cell.is_alive() {
+            game.fill_viewport();
+        }
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+    use crate::game::{traits::CellGame as _, CellGame};
+
+    #[test]
+    fn test_matches_cell_game() {
+        // An R-pentomino in the middle of a board large enough to never reach the border
+        let r_pentomino = [(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)];
+        let size = 256;
+        let mut dense = CellGame::<CellConway, ConwayRule>::new(size, size, CellConway::Dead);
+        let mut hashlife = HashLifeGame::<CellConway>::with_rule(4, 4, LifeLikeRule::conway());
+        let mut hashlife_pow2 = HashLifeGame::<CellConway>::with_rule(4, 4, LifeLikeRule::conway());
+        for &(x, y) in &r_pentomino {
+            dense.next_cell(size / 2 + x, size / 2 + y);
+            hashlife.set_cell(x as i64, y as i64, true);
+            hashlife_pow2.set_cell(x as i64, y as i64, true);
+        }
+        for _ in 0..64 {
+            dense.step();
+            hashlife.step();
+        }
+        hashlife_pow2.step_pow2(6);
+        assert_eq!(hashlife.generation(), 64);
+        assert_eq!(hashlife_pow2.generation(), 64);
+        let offset = -(size as i64 / 2);
+        let board = hashlife.to_board(offset, offset, size, size);
+        let board_pow2 = hashlife_pow2.to_board(offset, offset, size, size);
+        assert!(dense.get_board().iter().eq(board.iter()));
+        assert!(dense.get_board().iter().eq(board_pow2.iter()));
+        assert_eq!(
+            hashlife.population() as usize,
+            board.iter().filter(|c| c.is_alive()).count()
+        );
+    }
+
+    #[test]
+    fn test_rejects_birth_on_zero() {
+        let mut game = HashLifeGame::<CellConway>::with_rule(4, 4, LifeLikeRule::conway());
+        assert_eq!(
+            game.set_rule_str("B03/S23"),
+            Err(ParseRuleError::UnsupportedBirthOnZero)
+        );
+        assert_eq!(game.rule(), LifeLikeRule::conway());
+        let b0 = "B0/S8".parse().unwrap();
+        assert!(check_rule(b0).is_err());
+        assert!(
+            std::panic::catch_unwind(|| HashLifeGame::<CellConway>::with_rule(4, 4, b0)).is_err()
+        );
+    }
+}
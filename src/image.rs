@@ -0,0 +1,237 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::cell::*;
+use crate::game_board::*;
+use crate::rgba::RGBA;
+
+pub mod recorder;
+
+/// The reasons a board cannot be written to or read from an image
+#[derive(Debug)]
+pub enum ImageError {
+    /// The file cannot be read or written
+    Io(std::io::Error),
+    /// The image cannot be encoded
+    Encoding(png::EncodingError),
+    /// The animated GIF cannot be encoded
+    Gif(gif::EncodingError),
+    /// The image is malformed
+    Decoding(png::DecodingError),
+    /// There is no color in the palette to map the pixels to
+    EmptyPalette,
+    /// No frame was recorded
+    EmptyRecording,
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot access the image file: {}", e),
+            Self::Encoding(e) => write!(f, "cannot encode the image: {}", e),
+            Self::Decoding(e) => write!(f, "cannot decode the image: {}", e),
+            Self::Gif(e) => write!(f, "cannot encode the GIF: {}", e),
+            Self::EmptyPalette => write!(f, "the palette is empty"),
+            Self::EmptyRecording => write!(f, "the recording has no frames"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Encoding(e) => Some(e),
+            Self::Decoding(e) => Some(e),
+            Self::Gif(e) => Some(e),
+            Self::EmptyPalette | Self::EmptyRecording => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<png::EncodingError> for ImageError {
+    fn from(e: png::EncodingError) -> Self {
+        Self::Encoding(e)
+    }
+}
+
+impl From<gif::EncodingError> for ImageError {
+    fn from(e: gif::EncodingError) -> Self {
+        Self::Gif(e)
+    }
+}
+
+impl From<png::DecodingError> for ImageError {
+    fn from(e: png::DecodingError) -> Self {
+        Self::Decoding(e)
+    }
+}
+
+/// Get every state of the cell type together with its color, as a palette for reading images
+pub fn palette<C: FiniteCell>() -> Vec<(RGBA, C)> {
+    C::states()
+        .into_iter()
+        .map(|cell| (cell.to_rgba(), cell))
+        .collect()
+}
+
+/// Write the board as a PNG with every cell drawn as a square of scale x scale pixels in the color of `Cell::to_rgba`.
+/// The colors are written without their alpha channel, so every cell is opaque.
+/// Cells of hexagonal boards are drawn at their axial coordinates, without the shift of the rows.
+pub fn write_png<C: Cell>(
+    board: &GameBoard<C>,
+    scale: usize,
+    writer: impl Write,
+) -> Result<(), ImageError> {
+    let (width, height) = board.dim();
+    let rgba: Vec<u8> = board
+        .iter()
+        .flat_map(|cell| cell.to_rgba().get_raw())
+        .collect();
+    let rgb = without_alpha(&scale_frame(&rgba, width, scale));
+    let mut encoder =
+        png::Encoder::new(writer, image_size(width, scale), image_size(height, scale));
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&rgb)?;
+    Ok(())
+}
+
+/// Write the board to a PNG file, see `write_png`
+pub fn save_png<C: Cell>(
+    board: &GameBoard<C>,
+    scale: usize,
+    path: impl AsRef<Path>,
+) -> Result<(), ImageError> {
+    write_png(board, scale, BufWriter::new(File::create(path)?))
+}
+
+/// Read a PNG into a board with one cell per pixel and the given boundary.
+/// Each pixel becomes the cell of the palette whose color is nearest to it, ignoring the alpha channel.
+pub fn read_png<C: Cell>(
+    reader: impl Read,
+    palette: &[(RGBA, C)],
+    boundary: Boundary<C>,
+) -> Result<GameBoard<C>, ImageError> {
+    let (_, first) = palette.first().ok_or(ImageError::EmptyPalette)?;
+    let mut decoder = png::Decoder::new(reader);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer)?;
+    let channels = info.color_type.samples();
+    let (width, height) = (info.width as usize, info.height as usize);
+    let mut board = GameBoard::with_boundary(width, height, *first, boundary);
+    for (cell, pixel) in board
+        .iter_mut()
+        .zip(buffer[..info.buffer_size()].chunks_exact(channels))
+    {
+        // Gray pixels only have a single channel for all three colors
+        let rgb = match pixel.len() {
+            1 | 2 => [pixel[0]; 3],
+            _ => [pixel[0], pixel[1], pixel[2]],
+        };
+        *cell = nearest(palette, rgb);
+    }
+    board.update_border();
+    Ok(board)
+}
+
+/// Read a PNG file into a board, see `read_png`
+pub fn load_png<C: Cell>(
+    path: impl AsRef<Path>,
+    palette: &[(RGBA, C)],
+    boundary: Boundary<C>,
+) -> Result<GameBoard<C>, ImageError> {
+    read_png(BufReader::new(File::open(path)?), palette, boundary)
+}
+
+/// Get the cell of the palette whose color has the smallest squared distance to the given color
+fn nearest<C: Cell>(palette: &[(RGBA, C)], rgb: [u8; 3]) -> C {
+    let distance = |color: &RGBA| {
+        color.get_raw()[..3]
+            .iter()
+            .zip(rgb)
+            .map(|(&a, b)| (a as i32 - b as i32).pow(2))
+            .sum::<i32>()
+    };
+    palette
+        .iter()
+        .min_by_key(|(color, _)| distance(color))
+        .map(|&(_, cell)| cell)
+        .expect("The palette is not empty")
+}
+
+/// Enlarge a frame of RGBA pixels with the given width, so every pixel becomes a square of scale x scale pixels
+pub(crate) fn scale_frame(frame: &[u8], width: usize, scale: usize) -> Vec<u8> {
+    assert!(scale > 0, "The scale has to be at least 1!");
+    frame
+        .chunks_exact(width * 4)
+        .flat_map(|row| {
+            let scaled_row: Vec<u8> = row
+                .chunks_exact(4)
+                .flat_map(|pixel| std::iter::repeat_n(pixel, scale).flatten().copied())
+                .collect();
+            std::iter::repeat_n(scaled_row, scale).flatten()
+        })
+        .collect()
+}
+
+/// Drop the alpha channel of a frame of RGBA pixels
+pub(crate) fn without_alpha(frame: &[u8]) -> Vec<u8> {
+    frame
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect()
+}
+
+/// The size of a side of the image in pixels, which PNG limits to u32
+pub(crate) fn image_size(cells: usize, scale: usize) -> u32 {
+    (cells * scale).try_into().expect("The image is too large!")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+
+    #[test]
+    fn test_png_round_trip() {
+        let mut board = GameBoard::new(4, 3, CellConway::Dead);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set(x, y, CellConway::Alive);
+        }
+        let palette = palette::<CellConway>();
+        let mut png = Vec::new();
+        write_png(&board, 1, &mut png).unwrap();
+        let read = read_png(png.as_slice(), &palette, Boundary::Toroidal).unwrap();
+        assert!(read.iter().eq(board.iter()));
+        assert_eq!(read.boundary(), &Boundary::Toroidal);
+        // Every cell of a scaled image covers several pixels
+        png.clear();
+        write_png(&board, 3, &mut png).unwrap();
+        let scaled = read_png(png.as_slice(), &palette, Boundary::Toroidal).unwrap();
+        assert_eq!(scaled.dim(), (12, 9));
+        assert_eq!(scaled.get(4, 1), Some(&CellConway::Alive));
+        assert_eq!(scaled.get(5, 5), Some(&CellConway::Dead));
+        // Colors which are not in the palette map to the nearest one
+        assert_eq!(nearest(&palette, [0xC0, 0xB0, 0xD0]), CellConway::Alive);
+        assert_eq!(nearest(&palette, [0x20, 0x30, 0x10]), CellConway::Dead);
+        assert!(matches!(
+            read_png(
+                png.as_slice(),
+                &[] as &[(RGBA, CellConway)],
+                Boundary::Toroidal
+            ),
+            Err(ImageError::EmptyPalette)
+        ));
+    }
+}
@@ -0,0 +1,291 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use super::{image_size, scale_frame, without_alpha, ImageError};
+use crate::cell::Cell;
+use crate::game::traits::CellGame;
+use crate::visuals::{buffer_size, draw_board};
+
+/// The animated image formats a Recorder can write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingFormat {
+    Gif,
+    Apng,
+}
+
+impl RecordingFormat {
+    /// Choose the format by the extension of the path, APNG for `png` and `apng` and GIF otherwise
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("png" | "apng") => Self::Apng,
+            _ => Self::Gif,
+        }
+    }
+}
+
+/// Records the boards of a game as the frames of an animated GIF or APNG.
+///
+/// The frames are drawn like the window draws them, so cells for which `overwrite_decaying` is false
+/// fade in over the previous frame according to the decay multiplier.
+/// The frames are kept in memory until the recording is written, taking four bytes per cell each,
+/// so long recordings of large boards should limit the number of frames with `set_max_frames`.
+pub struct Recorder<C: Cell> {
+    format: RecordingFormat,
+    frame_step: usize,
+    scale: usize,
+    frame_delay: Duration,
+    max_frames: usize,
+    decay_multiplier: f32,
+    overwrite_decaying: fn(&C) -> bool,
+    // The pixels of the last frame, which the next frame is blended with
+    pixels: Vec<u8>,
+    frame_dim: (usize, usize),
+    // The pixels of every frame together with how long it is shown
+    frames: Vec<(Vec<u8>, Duration)>,
+    // Generations since the last frame
+    generations: usize,
+}
+
+impl<C: Cell> Recorder<C> {
+    /// Construct a recorder capturing every generation at one pixel per cell, with 100ms between frames, no decay
+    /// and no limit on the number of frames
+    pub fn new(format: RecordingFormat, overwrite_decaying: fn(&C) -> bool) -> Self {
+        Self {
+            format,
+            frame_step: 1,
+            scale: 1,
+            frame_delay: Duration::from_millis(100),
+            max_frames: usize::MAX,
+            decay_multiplier: 1.0,
+            overwrite_decaying,
+            pixels: Vec::new(),
+            frame_dim: (0, 0),
+            frames: Vec::new(),
+            generations: 0,
+        }
+    }
+    /// Get the format the recording is written in
+    pub fn format(&self) -> RecordingFormat {
+        self.format
+    }
+    /// Only capture every n-th generation
+    pub fn set_frame_step(&mut self, n: usize) {
+        assert!(n > 0, "The frame step has to be at least 1!");
+        self.frame_step = n;
+    }
+    /// Draw every cell as a square of scale x scale pixels
+    pub fn set_scale(&mut self, scale: usize) {
+        assert!(scale > 0, "The scale has to be at least 1!");
+        self.scale = scale;
+    }
+    /// Set how long the frames captured from now on are shown. GIFs round the delay down to hundredths of a second.
+    pub fn set_frame_delay(&mut self, frame_delay: Duration) {
+        self.frame_delay = frame_delay;
+    }
+    /// Set how fast the colors of decaying cells fade in, with 1.0 for instantly like in `Visuals`
+    pub fn set_decay_multiplier(&mut self, decay_multiplier: f32) {
+        assert!(
+            (0.0..=1.0).contains(&decay_multiplier),
+            "Decay multiplier should only be between 0 and 1!"
+        );
+        self.decay_multiplier = decay_multiplier;
+    }
+    /// Stop capturing frames once the given number of frames is recorded
+    pub fn set_max_frames(&mut self, max_frames: usize) {
+        assert!(
+            max_frames > 0,
+            "The recording has to hold at least 1 frame!"
+        );
+        self.max_frames = max_frames;
+    }
+    /// Get the number of frames recorded so far
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+    /// Whether the maximum number of frames is recorded, after which no more frames are captured
+    pub fn is_full(&self) -> bool {
+        self.frames.len() >= self.max_frames
+    }
+    /// Draw the board of the game as the next frame, unless the recording is full.
+    /// The board has to keep its dimension during the recording.
+    pub fn capture<G: CellGame<Cell = C>>(&mut self, game: &G) {
+        if self.is_full() {
+            return;
+        }
+        let board = game.get_board();
+        let (width, height) = board.dim();
+        let frame_dim = buffer_size(width, height, board.topology());
+        if self.frames.is_empty() {
+            self.pixels = vec![0; frame_dim.0 * frame_dim.1 * 4];
+            self.frame_dim = frame_dim;
+        }
+        assert_eq!(
+            self.frame_dim, frame_dim,
+            "The board changed its dimension during the recording!"
+        );
+        draw_board(
+            &mut self.pixels,
+            board,
+            self.overwrite_decaying,
+            self.decay_multiplier,
+        );
+        self.frames.push((self.pixels.clone(), self.frame_delay));
+        self.generations = 0;
+    }
+    /// Note that the game advanced by the given number of generations, and capture its board if a frame is due.
+    /// Generations beyond the last due frame count towards the next one.
+    /// The board has to be up to date, see `CellGame::update_board`.
+    pub fn advance<G: CellGame<Cell = C>>(&mut self, game: &G, generations: usize) {
+        self.generations += generations;
+        if self.generations >= self.frame_step {
+            let remainder = self.generations % self.frame_step;
+            self.capture(game);
+            self.generations = remainder;
+        }
+    }
+    /// Step the game the given number of times while capturing every frame step.
+    /// The board the game starts with is captured first if the recording has no frames yet.
+    pub fn record<G: CellGame<Cell = C>>(&mut self, game: &mut G, generations: usize) {
+        if self.frames.is_empty() {
+            game.update_board();
+            self.capture(game);
+        }
+        for _ in 0..generations {
+            game.step();
+            game.update_board();
+            self.advance(game, 1);
+        }
+    }
+    /// Write the recorded frames as an endlessly looping animation
+    pub fn write(&self, writer: impl Write) -> Result<(), ImageError> {
+        if self.frames.is_empty() {
+            return Err(ImageError::EmptyRecording);
+        }
+        match self.format {
+            RecordingFormat::Gif => self.write_gif(writer),
+            RecordingFormat::Apng => self.write_apng(writer),
+        }
+    }
+    /// Write the recorded frames to a file, see `write`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+    fn write_gif(&self, writer: impl Write) -> Result<(), ImageError> {
+        let (width, height) = self.image_dim();
+        let (width, height) = (gif_size(width), gif_size(height));
+        let mut encoder = gif::Encoder::new(writer, width, height, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for (frame, delay) in &self.frames {
+            let mut pixels = scale_frame(frame, self.frame_dim.0, self.scale);
+            // Transparent pixels would let the previous frames shine through
+            pixels.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 0xFF);
+            let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 10);
+            frame.delay = (delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+            encoder.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+    fn write_apng(&self, writer: impl Write) -> Result<(), ImageError> {
+        let (width, height) = self.image_dim();
+        let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(self.frames.len() as u32, 0)?;
+        let mut writer = encoder.write_header()?;
+        for (frame, delay) in &self.frames {
+            writer.set_frame_delay(delay.as_millis().min(u16::MAX as u128) as u16, 1000)?;
+            writer.write_image_data(&without_alpha(&scale_frame(
+                frame,
+                self.frame_dim.0,
+                self.scale,
+            )))?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+    /// The size of the images in pixels
+    fn image_dim(&self) -> (usize, usize) {
+        let (width, height) = self.frame_dim;
+        (
+            image_size(width, self.scale) as usize,
+            image_size(height, self.scale) as usize,
+        )
+    }
+}
+
+fn gif_size(pixels: usize) -> u16 {
+    pixels
+        .try_into()
+        .expect("The image is too large for a GIF!")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+    use crate::game::traits::CellGame as _;
+    use crate::game::CellGame;
+    use crate::game_board::{Boundary, GameBoard};
+
+    #[test]
+    fn test_recording() {
+        let mut board = GameBoard::with_boundary(6, 6, CellConway::Dead, Boundary::Toroidal);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            board.set(x, y, CellConway::Alive);
+        }
+        let mut game = CellGame::<CellConway, ConwayRule>::from_board(board);
+        let mut recorder = Recorder::new(RecordingFormat::Apng, |_| true);
+        recorder.set_frame_step(2);
+        recorder.set_scale(2);
+        recorder.record(&mut game, 4);
+        // Only the frames captured after changing the delay are shown for longer
+        recorder.set_frame_delay(Duration::from_millis(250));
+        recorder.record(&mut game, 4);
+        // Generations 0, 2, 4, 6 and 8
+        assert_eq!(recorder.frame_count(), 5);
+        assert_eq!(game.generation(), 8);
+        let mut apng = Vec::new();
+        recorder.write(&mut apng).unwrap();
+        let mut decoder = png::Decoder::new(apng.as_slice()).read_info().unwrap();
+        let info = decoder.info();
+        assert_eq!((info.width, info.height), (12, 12));
+        assert_eq!(info.animation_control.map(|a| a.num_frames), Some(5));
+        let mut buffer = vec![0; decoder.output_buffer_size()];
+        let mut delays = Vec::new();
+        while decoder.next_frame(&mut buffer).is_ok() {
+            delays.extend(
+                decoder
+                    .info()
+                    .frame_control
+                    .map(|control| control.delay_num),
+            );
+        }
+        assert_eq!(delays, [100, 100, 100, 250, 250]);
+        // A frame is due after every second generation, even if they are reported unevenly
+        let mut uneven = Recorder::new(RecordingFormat::Gif, |_| true);
+        uneven.set_frame_step(2);
+        uneven.capture(&game);
+        for generations in [3, 1, 1, 1] {
+            uneven.advance(&game, generations);
+        }
+        assert_eq!(uneven.frame_count(), 4);
+        let mut gif = Vec::new();
+        let mut recorder = Recorder::new(RecordingFormat::Gif, |_| true);
+        assert!(matches!(
+            recorder.write(&mut gif),
+            Err(ImageError::EmptyRecording)
+        ));
+        recorder.set_max_frames(2);
+        recorder.record(&mut game, 3);
+        assert!(recorder.is_full());
+        assert_eq!(recorder.frame_count(), 2);
+        recorder.write(&mut gif).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+    }
+}
@@ -1,13 +1,33 @@
+pub mod bit_game;
 pub mod cell;
+pub mod compiled_rule;
 pub mod default_game_runner;
 pub mod default_window;
 pub mod game;
 pub mod game_board;
 pub mod game_rules;
+pub mod generations;
+pub mod hashlife;
+pub mod image;
+pub mod life_like;
+pub mod pattern;
 mod globals;
 pub mod rgba;
+pub mod rule_table;
+#[cfg(feature = "serde")]
+pub mod snapshot;
+pub mod soup;
+pub mod sparse_game;
 pub mod visuals;
 
+// Lets the code generated by `#[derive(Cell)]` refer to this crate by name from within it as well
+extern crate self as cell_engine_rs;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use rand;
+}
+
 #[macro_export]
 macro_rules! dprintln {
     ($($rest:tt)*) => {
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use crate::cell::*;
+use crate::game_board::*;
+use crate::game_rules::*;
+
+/// An outer-totalistic rule for two-state cells on the Moore neighborhood.
+/// A dead cell is born if its number of alive neighbors is in the birth set,
+/// an alive cell survives if its number of alive neighbors is in the survival set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeLikeRule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+impl Default for LifeLikeRule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl LifeLikeRule {
+    /// Construct a rule from the neighbor counts leading to birth and survival. Counts above 8 are ignored.
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let mut rule = Self {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+        birth
+            .iter()
+            .filter(|&&n| n <= 8)
+            .for_each(|&n| rule.birth[n] = true);
+        survival
+            .iter()
+            .filter(|&&n| n <= 8)
+            .for_each(|&n| rule.survival[n] = true);
+        rule
+    }
+    /// Conway's Game of Life, B3/S23
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+    /// Whether a cell is alive in the next generation, given whether it is alive now and its number of alive neighbors.
+    pub fn next_alive(&self, alive: bool, alive_neighbors: usize) -> bool {
+        if alive {
+            self.survival[alive_neighbors]
+        } else {
+            self.birth[alive_neighbors]
+        }
+    }
+    /// Whether a dead cell is born with the given number of alive neighbors
+    pub fn birth(&self, alive_neighbors: usize) -> bool {
+        self.birth[alive_neighbors]
+    }
+    /// Whether an alive cell survives with the given number of alive neighbors
+    pub fn survival(&self, alive_neighbors: usize) -> bool {
+        self.survival[alive_neighbors]
+    }
+}
+
+/// Writes the rule in the form `B36/S23`
+impl fmt::Display for LifeLikeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |set: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| set[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))
+    }
+}
+
+/// Parses Golly-style rule strings, either with prefixes like `B36/S23` and `S23/B36`,
+/// or without them in the survival/birth order like `23/36`. Letters are case-insensitive.
+impl FromStr for LifeLikeRule {
+    type Err = ParseRuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('/').collect();
+        if parts.len() != 2 {
+            return Err(ParseRuleError::WrongNumberOfParts {
+                expected: 2,
+                found: parts.len(),
+            });
+        }
+        let (birth, survival) = match (
+            split_prefix(parts[0], &['B', 'S'])?,
+            split_prefix(parts[1], &['B', 'S'])?,
+        ) {
+            ((Some('B'), birth), (Some('S'), survival))
+            | ((Some('S'), survival), (Some('B'), birth)) => (birth, survival),
+            ((None, survival), (None, birth)) => (birth, survival),
+            _ => return Err(ParseRuleError::InvalidPrefixes),
+        };
+        Ok(Self::new(
+            &parse_counts(birth, 8)?,
+            &parse_counts(survival, 8)?,
+        ))
+    }
+}
+
+/// Split one of the given uppercase letters off a part of a rule string, if it starts with one in any case.
+pub(crate) fn split_prefix<'a>(
+    part: &'a str,
+    letters: &[char],
+) -> Result<(Option<char>, &'a str), ParseRuleError> {
+    match part.chars().next() {
+        Some(c) if letters.contains(&c.to_ascii_uppercase()) => {
+            Ok((Some(c.to_ascii_uppercase()), &part[1..]))
+        }
+        Some(c) if !c.is_ascii_digit() => Err(ParseRuleError::InvalidCharacter(c)),
+        _ => Ok((None, part)),
+    }
+}
+
+/// Parse a part of a rule string consisting of single digit neighbor counts up to the given maximum.
+pub(crate) fn parse_counts(digits: &str, max: usize) -> Result<Vec<usize>, ParseRuleError> {
+    digits
+        .chars()
+        .map(|c| {
+            c.to_digit(10)
+                .map(|n| n as usize)
+                .filter(|&n| n <= max)
+                .ok_or(ParseRuleError::InvalidCharacter(c))
+        })
+        .collect()
+}
+
+/// A LifeLikeRule applied to any two-state cell, so a CellGame can play a rule chosen at runtime.
+#[derive(Debug)]
+pub struct LifeLike<C: BinaryCell> {
+    rule: LifeLikeRule,
+    cell: PhantomData<C>,
+}
+
+impl<C: BinaryCell> LifeLike<C> {
+    pub fn new(rule: LifeLikeRule) -> Self {
+        Self {
+            rule,
+            cell: PhantomData,
+        }
+    }
+    /// Get the birth and survival sets of the rule
+    pub fn rule(&self) -> LifeLikeRule {
+        self.rule
+    }
+}
+
+impl<C: BinaryCell> Clone for LifeLike<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: BinaryCell> Copy for LifeLike<C> {}
+
+impl<C: BinaryCell> Default for LifeLike<C> {
+    fn default() -> Self {
+        Self::new(LifeLikeRule::default())
+    }
+}
+
+impl<C: BinaryCell> From<LifeLikeRule> for LifeLike<C> {
+    fn from(rule: LifeLikeRule) -> Self {
+        Self::new(rule)
+    }
+}
+
+impl<C: BinaryCell> ContextGameRule for LifeLike<C> {
+    type Cell = C;
+    fn apply_in_context(
+        &self,
+        cell: &Self::Cell,
+        neighbors: Neighborhood<Self::Cell>,
+        _context: &mut RuleContext,
+    ) -> Self::Cell {
+        let alive_neighbors = neighbors.count_where(|c| c.is_alive());
+        if self.rule.next_alive(cell.is_alive(), alive_neighbors) {
+            C::alive()
+        } else {
+            C::dead()
+        }
+    }
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+    fn parse_rule(rule: &str) -> Result<Self, ParseRuleError> {
+        rule.parse().map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule_strings() {
+        let highlife = LifeLikeRule::new(&[3, 6], &[2, 3]);
+        for s in ["B36/S23", "b36/s23", "S23/B36", "23/36", " B36/S23 "] {
+            assert_eq!(s.parse(), Ok(highlife));
+        }
+        assert_eq!(highlife.to_string(), "B36/S23");
+        assert_eq!("B/S".parse(), Ok(LifeLikeRule::new(&[], &[])));
+        assert_eq!(
+            "B3".parse::<LifeLikeRule>(),
+            Err(ParseRuleError::WrongNumberOfParts {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            "B39/S23".parse::<LifeLikeRule>(),
+            Err(ParseRuleError::InvalidCharacter('9'))
+        );
+        assert_eq!(
+            "B3/B23".parse::<LifeLikeRule>(),
+            Err(ParseRuleError::InvalidPrefixes)
+        );
+        assert_eq!(
+            "X3/S23".parse::<LifeLikeRule>(),
+            Err(ParseRuleError::InvalidCharacter('X'))
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+
+use crate::cell::*;
+use crate::game_board::*;
+
+pub mod life106;
+pub mod macrocell;
+pub mod plaintext;
+pub mod rle;
+
+/// The most cells a pattern read from a file may have, so malformed files cannot request huge allocations
+pub const MAX_CELLS: usize = 1 << 28;
+
+/// A rectangular pattern of cells given by their state indices, as read from or written to a pattern file.
+///
+/// The states are independent of a cell type, so a pattern can be placed on any board of FiniteCells
+/// whose cells have at least as many states as the pattern uses. State 0 is the dead or empty state.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    states: Vec<u8>,
+    /// Name of the pattern
+    pub name: Option<String>,
+    /// Author of the pattern
+    pub author: Option<String>,
+    /// Comment lines describing the pattern
+    pub comments: Vec<String>,
+    /// The rule the pattern is meant to be run with, such as `B3/S23`
+    pub rule: Option<String>,
+    /// Position of the top left cell relative to the origin of the pattern file, for formats giving positions
+    pub offset: (isize, isize),
+}
+
+/// The reasons a pattern cannot be read or placed
+#[derive(Debug)]
+pub enum PatternError {
+    /// The file cannot be read or written
+    Io(std::io::Error),
+    /// The content of the given line, counting from 1, is malformed
+    Parse { line: usize, message: String },
+    /// The pattern contains a state the cell type or the file format does not have
+    UnknownState(u8),
+    /// The pattern read from a file would have more than `MAX_CELLS` cells
+    TooLarge { width: usize, height: usize },
+    /// The pattern has no rows or no columns, so there is no board to construct from it
+    Empty,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot access the pattern file: {}", e),
+            Self::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            Self::UnknownState(state) => write!(f, "state {} is not supported", state),
+            Self::TooLarge { width, height } => write!(
+                f,
+                "a pattern of {}x{} cells is larger than {} cells",
+                width, height, MAX_CELLS
+            ),
+            Self::Empty => write!(f, "the pattern has no cells"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PatternError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+pub(crate) fn parse_error(line: usize, message: impl Into<String>) -> PatternError {
+    PatternError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+impl Pattern {
+    /// Construct an empty pattern of the given dimension, with every cell in state 0
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            states: vec![0; width * height],
+            ..Self::default()
+        }
+    }
+    /// Construct an empty pattern of the given dimension read from a file,
+    /// or an error if it would have more than `MAX_CELLS` cells
+    pub(crate) fn checked_new(width: usize, height: usize) -> Result<Self, PatternError> {
+        match width.checked_mul(height) {
+            Some(cells) if cells <= MAX_CELLS => Ok(Self::new(width, height)),
+            _ => Err(PatternError::TooLarge { width, height }),
+        }
+    }
+    /// Copy the region of the board with the given top left corner and dimension into a pattern.
+    /// Panics if the region does not lie within the board.
+    pub fn from_board<C: FiniteCell>(
+        board: &GameBoard<C>,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Self {
+        let (board_width, board_height) = board.dim();
+        assert!(
+            x + width <= board_width && y + height <= board_height,
+            "The region of the pattern does not lie within the board!"
+        );
+        assert!(
+            C::STATE_COUNT <= 256,
+            "Patterns cannot hold cells with more than 256 states!"
+        );
+        let mut pattern = Self::new(width, height);
+        for py in 0..height {
+            for px in 0..width {
+                let state = board.get(x + px, y + py).unwrap().state_index() as u8;
+                pattern.set(px, py, state);
+            }
+        }
+        pattern
+    }
+    /// Get a tuple containing the dimension of the pattern as (width, height)
+    pub fn dim(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    /// Get the state of the cell at the given coordinate. Panics if it lies outside of the pattern.
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        assert!(x < self.width && y < self.height);
+        self.states[y * self.width + x]
+    }
+    /// Set the state of the cell at the given coordinate. Panics if it lies outside of the pattern.
+    pub fn set(&mut self, x: usize, y: usize, state: u8) {
+        assert!(x < self.width && y < self.height);
+        self.states[y * self.width + x] = state;
+    }
+    /// Get the highest state of any cell, which is 0 for an empty pattern
+    pub fn max_state(&self) -> u8 {
+        self.states.iter().copied().max().unwrap_or(0)
+    }
+    /// Construct a board just large enough for the pattern, whose edges behave according to the boundary.
+    pub fn to_board<C: FiniteCell>(
+        &self,
+        boundary: Boundary<C>,
+    ) -> Result<GameBoard<C>, PatternError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(PatternError::Empty);
+        }
+        let mut board =
+            GameBoard::with_boundary(self.width, self.height, C::from_state_index(0), boundary);
+        self.stamp(&mut board, 0, 0)?;
+        Ok(board)
+    }
+    /// Overwrite the cells of the board covered by the pattern, with the top left corner of the pattern
+    /// placed at the given coordinate. The parts of the pattern reaching beyond the board are cut off.
+    pub fn stamp<C: FiniteCell>(
+        &self,
+        board: &mut GameBoard<C>,
+        x: isize,
+        y: isize,
+    ) -> Result<(), PatternError> {
+        let max_state = self.max_state();
+        if max_state as usize >= C::STATE_COUNT {
+            return Err(PatternError::UnknownState(max_state));
+        }
+        let (board_width, board_height) = board.dim();
+        for py in 0..self.height {
+            for px in 0..self.width {
+                let (bx, by) = (x + px as isize, y + py as isize);
+                if (0..board_width as isize).contains(&bx)
+                    && (0..board_height as isize).contains(&by)
+                {
+                    *board.get_mut(bx as usize, by as usize).unwrap() =
+                        C::from_state_index(self.get(px, py) as usize);
+                }
+            }
+        }
+        board.update_border();
+        Ok(())
+    }
+    /// Overwrite the cells of the board covered by the pattern, with the origin of the pattern file
+    /// placed at the given coordinate. The parts of the pattern reaching beyond the board are cut off.
+    pub fn stamp_at_origin<C: FiniteCell>(
+        &self,
+        board: &mut GameBoard<C>,
+        x: isize,
+        y: isize,
+    ) -> Result<(), PatternError> {
+        self.stamp(board, x + self.offset.0, y + self.offset.1)
+    }
+    /// Get the coordinates of all cells which are not in state 0, relative to the origin of the pattern file
+    pub(crate) fn live_cells(&self) -> impl Iterator<Item = (isize, isize, u8)> + '_ {
+        let (width, (x0, y0)) = (self.width, self.offset);
+        self.states
+            .iter()
+            .enumerate()
+            .filter(|(_, &state)| state != 0)
+            .map(move |(i, &state)| (x0 + (i % width) as isize, y0 + (i / width) as isize, state))
+    }
+    /// Fail if the pattern has states above 1, for formats only knowing dead and alive cells
+    pub(crate) fn check_two_states(&self) -> Result<(), PatternError> {
+        match self.max_state() {
+            0 | 1 => Ok(()),
+            state => Err(PatternError::UnknownState(state)),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use super::{parse_error, Pattern, PatternError};
+
+const HEADER: &str = "#Life 1.06";
+
+/// Read a pattern from a Life 1.06 file
+pub fn load(path: impl AsRef<Path>) -> Result<Pattern, PatternError> {
+    parse(&std::fs::read_to_string(path)?)
+}
+
+/// Write the pattern to a Life 1.06 file
+pub fn save(pattern: &Pattern, path: impl AsRef<Path>) -> Result<(), PatternError> {
+    Ok(std::fs::write(path, write(pattern)?)?)
+}
+
+/// Parse a pattern in the Life 1.06 format, which lists the signed coordinates `x y` of every alive cell
+/// after the header `#Life 1.06`.
+///
+/// The pattern spans the bounding box of the cells, whose top left corner becomes the offset of the pattern.
+/// Bounding boxes of more than `MAX_CELLS` cells are rejected, even if only a few cells are alive.
+/// The lines `#N`, `#O`, `#D` and `#R` hold the name, author, comments and rule.
+pub fn parse(content: &str) -> Result<Pattern, PatternError> {
+    let mut lines = content.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim() == HEADER => {}
+        _ => {
+            return Err(parse_error(
+                1,
+                format!("the file has to start with {}", HEADER),
+            ))
+        }
+    }
+    let mut pattern = Pattern::default();
+    let mut cells = Vec::new();
+    for (i, line) in lines {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            let text = comment.get(1..).unwrap_or_default().trim().to_string();
+            match comment.chars().next() {
+                Some('N') => pattern.name = Some(text),
+                Some('O') => pattern.author = Some(text),
+                Some('D') => pattern.comments.push(text),
+                Some('R') => pattern.rule = Some(text),
+                _ => {}
+            }
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        let coordinates = line
+            .split_whitespace()
+            .map(str::parse::<isize>)
+            .collect::<Result<Vec<_>, _>>();
+        match coordinates.as_deref() {
+            Ok(&[x, y]) => cells.push((x, y)),
+            _ => {
+                return Err(parse_error(
+                    i + 1,
+                    "expected the coordinates of a cell as x y",
+                ))
+            }
+        }
+    }
+    let x_min = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let y_min = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    // The distance between the coordinates can exceed isize, but not usize. Saturated sizes are too large anyway.
+    let side = |distance: usize| distance.saturating_add(1);
+    let width = cells.iter().map(|&(x, _)| side(x.abs_diff(x_min))).max();
+    let height = cells.iter().map(|&(_, y)| side(y.abs_diff(y_min))).max();
+    let mut bounded = Pattern::checked_new(width.unwrap_or(0), height.unwrap_or(0))?;
+    for (x, y) in cells {
+        bounded.set(x.abs_diff(x_min), y.abs_diff(y_min), 1);
+    }
+    Ok(Pattern {
+        width: bounded.width,
+        height: bounded.height,
+        states: bounded.states,
+        offset: (x_min, y_min),
+        ..pattern
+    })
+}
+
+/// Write the pattern in the Life 1.06 format, with the cells shifted by the offset of the pattern.
+/// Fails if the pattern has states above 1.
+pub fn write(pattern: &Pattern) -> Result<String, PatternError> {
+    pattern.check_two_states()?;
+    let mut life = format!("{}\n", HEADER);
+    for (tag, text) in [("N", &pattern.name), ("O", &pattern.author)] {
+        if let Some(text) = text {
+            life += &format!("#{} {}\n", tag, text);
+        }
+    }
+    for comment in &pattern.comments {
+        life += &format!("#D {}\n", comment);
+    }
+    if let Some(rule) = &pattern.rule {
+        life += &format!("#R {}\n", rule);
+    }
+    for (x, y, _) in pattern.live_cells() {
+        life += &format!("{} {}\n", x, y);
+    }
+    Ok(life)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+    use crate::game_board::*;
+
+    #[test]
+    fn test_life106_round_trip() {
+        let content =
+            "#Life 1.06\n#N Glider\n#D The smallest spaceship.\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+        let glider = parse(content).unwrap();
+        assert_eq!((glider.dim(), glider.offset), ((3, 3), (-1, -1)));
+        assert_eq!(write(&glider).unwrap(), content);
+        // The origin of the file lands on the chosen cell
+        let mut board = GameBoard::new(5, 5, CellConway::Dead);
+        glider.stamp_at_origin(&mut board, 2, 2).unwrap();
+        assert_eq!(board.get(2, 1), Some(&CellConway::Alive));
+        assert_eq!(board.get(1, 3), Some(&CellConway::Alive));
+        assert!(matches!(
+            parse("#Life 1.06\n0 0\n1 x\n").unwrap_err(),
+            PatternError::Parse { line: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_life106_rejects_huge_bounding_boxes() {
+        let far_apart = format!("#Life 1.06\n{} 0\n{} 1\n", isize::MIN, isize::MAX);
+        assert!(matches!(
+            parse(&far_apart),
+            Err(PatternError::TooLarge {
+                width: usize::MAX,
+                height: 2
+            })
+        ));
+        assert!(matches!(
+            parse("#Life 1.06\n-100000 0\n100000 2000\n"),
+            Err(PatternError::TooLarge {
+                width: 200001,
+                height: 2001
+            })
+        ));
+        let corners = parse("#Life 1.06\n-1000 0\n1000 20\n").unwrap();
+        assert_eq!((corners.dim(), corners.offset), ((2001, 21), (-1000, 0)));
+        assert_eq!(corners.get(2000, 20), 1);
+    }
+}
@@ -0,0 +1,200 @@
+use std::path::Path;
+
+use super::{parse_error, PatternError};
+use crate::cell::BinaryCell;
+use crate::hashlife::{check_rule, HashLifeGame, MacrocellNode, MAX_LEVEL};
+use crate::life_like::LifeLikeRule;
+
+const HEADER: &str = "[M2]";
+
+/// Read a macrocell file into a HashLifeGame with a viewport of the given size
+pub fn load<C: BinaryCell>(
+    path: impl AsRef<Path>,
+    width: usize,
+    height: usize,
+) -> Result<HashLifeGame<C>, PatternError> {
+    parse(&std::fs::read_to_string(path)?, width, height)
+}
+
+/// Write the cells and the rule of the game to a macrocell file
+pub fn save<C: BinaryCell>(
+    game: &HashLifeGame<C>,
+    path: impl AsRef<Path>,
+) -> Result<(), PatternError> {
+    Ok(std::fs::write(path, write(game))?)
+}
+
+/// Parse a two-state pattern in the macrocell format of Golly into a HashLifeGame with a viewport of the given size.
+///
+/// The file lists the nodes of a quadtree bottom up after the header `[M2]`, with the root last.
+/// Leaves of 8x8 cells are written like `.*$..*$***$`, with `*` for alive cells and `$` ending a row.
+/// Other nodes are written like `4 1 0 0 2`: their level, so they are 2^level cells wide,
+/// followed by the children nw, ne, sw and se, each given by its number among the nodes or 0 if it is empty.
+/// The center of the root is placed at the origin of the game, and the rule is taken from the line `#R`.
+pub fn parse<C: BinaryCell>(
+    content: &str,
+    width: usize,
+    height: usize,
+) -> Result<HashLifeGame<C>, PatternError> {
+    let mut lines = content.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.starts_with(HEADER) => {}
+        _ => {
+            return Err(parse_error(
+                1,
+                format!("the file has to start with {}", HEADER),
+            ))
+        }
+    }
+    let mut rule = LifeLikeRule::default();
+    let mut nodes = Vec::new();
+    let mut levels = Vec::new();
+    for (i, line) in lines {
+        let (number, line) = (i + 1, line.trim());
+        if let Some(comment) = line.strip_prefix("#R") {
+            rule = comment
+                .trim()
+                .parse()
+                .and_then(check_rule)
+                .map_err(|e| parse_error(number, format!("invalid rule: {}", e)))?;
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let node = if line.starts_with(['.', '*', '$']) {
+            parse_leaf(line, number)?
+        } else {
+            parse_node(line, number, &levels)?
+        };
+        levels.push(match node {
+            MacrocellNode::Leaf(_) => 3,
+            MacrocellNode::Node { level, .. } => level,
+        });
+        nodes.push(node);
+    }
+    let mut game = HashLifeGame::with_rule(width, height, rule);
+    game.set_macrocell(&nodes);
+    Ok(game)
+}
+
+fn parse_leaf(line: &str, number: usize) -> Result<MacrocellNode, PatternError> {
+    let mut rows = [0u8; 8];
+    let (mut x, mut y) = (0, 0);
+    for c in line.chars() {
+        match c {
+            '$' => (x, y) = (0, y + 1),
+            '.' | '*' if x < 8 && y < 8 => {
+                rows[y] |= ((c == '*') as u8) << x;
+                x += 1;
+            }
+            '.' | '*' => return Err(parse_error(number, "the leaf is larger than 8x8 cells")),
+            c => return Err(parse_error(number, format!("invalid character '{}'", c))),
+        }
+    }
+    Ok(MacrocellNode::Leaf(rows))
+}
+
+fn parse_node(line: &str, number: usize, levels: &[u8]) -> Result<MacrocellNode, PatternError> {
+    let numbers = line
+        .split_whitespace()
+        .map(str::parse::<usize>)
+        .collect::<Result<Vec<_>, _>>();
+    let (level, children) = match numbers.as_deref() {
+        Ok(&[level, nw, ne, sw, se]) => (level, [nw, ne, sw, se]),
+        _ => {
+            return Err(parse_error(
+                number,
+                "expected a leaf or a level followed by four children",
+            ))
+        }
+    };
+    if level <= 3 {
+        return Err(parse_error(
+            number,
+            "only two-state patterns with leaves of 8x8 cells are supported",
+        ));
+    }
+    if level >= MAX_LEVEL as usize {
+        return Err(parse_error(number, format!("level {} is too large", level)));
+    }
+    let level = level as u8;
+    for child in children {
+        match child.checked_sub(1).map(|index| levels.get(index)) {
+            None => {}
+            Some(Some(&child_level)) if child_level == level - 1 => {}
+            Some(Some(_)) => {
+                return Err(parse_error(
+                    number,
+                    format!("node {} is not of level {}", child, level - 1),
+                ))
+            }
+            Some(None) => {
+                return Err(parse_error(
+                    number,
+                    format!("node {} is not defined before", child),
+                ))
+            }
+        }
+    }
+    Ok(MacrocellNode::Node { level, children })
+}
+
+/// Write the cells and the rule of the game in the macrocell format
+pub fn write<C: BinaryCell>(game: &HashLifeGame<C>) -> String {
+    let mut macrocell = format!("{} (cell-engine-rs)\n#R {}\n", HEADER, game.rule());
+    for node in game.macrocell() {
+        match node {
+            MacrocellNode::Leaf(rows) => {
+                // Dead cells at the end of a row and empty rows at the end of the leaf are left out
+                let height = rows.iter().rposition(|&row| row != 0).map_or(0, |y| y + 1);
+                for row in &rows[..height] {
+                    let cells = 8 - row.leading_zeros() as usize;
+                    macrocell.extend((0..cells).map(|x| if row >> x & 1 == 1 { '*' } else { '.' }));
+                    macrocell.push('$');
+                }
+            }
+            MacrocellNode::Node { level, children } => {
+                let [nw, ne, sw, se] = children;
+                macrocell += &format!("{} {} {} {} {}", level, nw, ne, sw, se);
+            }
+        }
+        macrocell.push('\n');
+    }
+    macrocell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+
+    #[test]
+    fn test_macrocell_round_trip() {
+        // A glider in the south east quadrant of a node of 16x16 cells, so its top left corner is at the origin
+        let content = "[M2] (golly 4.2)\n#R B36/S23\n.*$..*$***$\n4 0 0 0 1\n";
+        let mut game = parse::<CellConway>(content, 8, 8).unwrap();
+        assert_eq!(game.rule().to_string(), "B36/S23");
+        assert_eq!(game.population(), 5);
+        assert!(game.get_cell(1, 0) && game.get_cell(0, 2) && !game.get_cell(0, 0));
+        // Cells far apart share the empty nodes between them
+        game.set_cell(1 << 40, -(1 << 40), true);
+        let written = write(&game);
+        let read = parse::<CellConway>(&written, 8, 8).unwrap();
+        assert_eq!(read.population(), 6);
+        assert!(read.get_cell(1 << 40, -(1 << 40)) && read.get_cell(2, 2));
+        assert_eq!(write(&read), written);
+        // The viewport shows the window starting at the origin
+        let board = read.to_board(0, 0, 3, 3);
+        assert_eq!(board.get(2, 1), Some(&CellConway::Alive));
+        assert!(matches!(
+            parse::<CellConway>("[M2]\n.*$\n4 0 0 0 2\n", 8, 8).err(),
+            Some(PatternError::Parse { line: 3, .. })
+        ));
+        // HashLife cannot play rules turning on empty space
+        assert!(matches!(
+            parse::<CellConway>("[M2]\n#R B03/S23\n.*$\n", 8, 8).err(),
+            Some(PatternError::Parse { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use super::{parse_error, Pattern, PatternError};
+
+/// Read a pattern from a plaintext `.cells` file
+pub fn load(path: impl AsRef<Path>) -> Result<Pattern, PatternError> {
+    parse(&std::fs::read_to_string(path)?)
+}
+
+/// Write the pattern to a plaintext `.cells` file
+pub fn save(pattern: &Pattern, path: impl AsRef<Path>) -> Result<(), PatternError> {
+    Ok(std::fs::write(path, write(pattern)?)?)
+}
+
+/// Parse a pattern in the plaintext format of the LifeWiki, with `.` for dead and `O` for alive cells.
+///
+/// Lines starting with `!` are comments, of which `!Name:` and `!Author:` hold the name and author.
+/// Rows may leave out dead cells at their end, the width of the pattern is that of the longest row.
+pub fn parse(content: &str) -> Result<Pattern, PatternError> {
+    let mut pattern = Pattern::default();
+    let mut rows = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(comment) = line.strip_prefix('!') {
+            if let Some(name) = comment.strip_prefix("Name:") {
+                pattern.name = Some(name.trim().to_string());
+            } else if let Some(author) = comment.strip_prefix("Author:") {
+                pattern.author = Some(author.trim().to_string());
+            } else {
+                pattern.comments.push(comment.trim().to_string());
+            }
+            continue;
+        }
+        let row = line
+            .chars()
+            .map(|c| match c {
+                '.' => Ok(0),
+                'O' | '*' => Ok(1),
+                c => Err(parse_error(i + 1, format!("invalid character '{}'", c))),
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+        rows.push(row);
+    }
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut cells = Pattern::new(width, rows.len());
+    for (y, row) in rows.iter().enumerate() {
+        for (x, &state) in row.iter().enumerate() {
+            cells.set(x, y, state);
+        }
+    }
+    Ok(Pattern {
+        width: cells.width,
+        height: cells.height,
+        states: cells.states,
+        ..pattern
+    })
+}
+
+/// Write the pattern in the plaintext format. Fails if the pattern has states above 1.
+pub fn write(pattern: &Pattern) -> Result<String, PatternError> {
+    pattern.check_two_states()?;
+    let mut cells = String::new();
+    if let Some(name) = &pattern.name {
+        cells += &format!("!Name: {}\n", name);
+    }
+    if let Some(author) = &pattern.author {
+        cells += &format!("!Author: {}\n", author);
+    }
+    for comment in &pattern.comments {
+        cells += &format!("!{}\n", comment);
+    }
+    let (width, height) = pattern.dim();
+    for y in 0..height {
+        cells.extend((0..width).map(|x| if pattern.get(x, y) == 0 { '.' } else { 'O' }));
+        cells.push('\n');
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::CellConway;
+    use crate::game_board::Boundary;
+
+    #[test]
+    fn test_plaintext_round_trip() {
+        let content =
+            "!Name: Glider\n!Author: Richard K. Guy\n!The smallest spaceship.\n.O.\n..O\nOOO\n";
+        let glider = parse(content).unwrap();
+        assert_eq!(glider.comments, ["The smallest spaceship."]);
+        assert_eq!(write(&glider).unwrap(), content);
+        assert_eq!(parse(".O\n\nO").unwrap().dim(), (2, 3));
+        assert!(matches!(
+            parse("!comment\n.O.\n.o.").unwrap_err(),
+            PatternError::Parse { line: 3, .. }
+        ));
+        let empty = parse("!Name: Nothing\n").unwrap();
+        assert!(matches!(
+            empty.to_board::<CellConway>(Boundary::Toroidal),
+            Err(PatternError::Empty)
+        ));
+    }
+}
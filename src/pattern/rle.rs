@@ -0,0 +1,319 @@
+use std::path::Path;
+
+use super::{parse_error, Pattern, PatternError};
+
+/// The longest line written to an RLE file, as recommended by the format
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Read a pattern from an RLE file
+pub fn load(path: impl AsRef<Path>) -> Result<Pattern, PatternError> {
+    parse(&std::fs::read_to_string(path)?)
+}
+
+/// Write the pattern to an RLE file
+pub fn save(pattern: &Pattern, path: impl AsRef<Path>) -> Result<(), PatternError> {
+    Ok(std::fs::write(path, write(pattern))?)
+}
+
+/// Parse a pattern in the run length encoded format, like `x = 3, y = 3, rule = B3/S23` followed by `bo$2bo$3o!`.
+///
+/// Two-state patterns use `b` for dead and `o` for alive cells. Multi-state patterns use `.` for state 0,
+/// `A` to `X` for the states 1 to 24 and a prefix from `p` to `y` for each further 24 states.
+/// The lines `#N`, `#O` and `#C` before the header hold the name, author and comments.
+pub fn parse(content: &str) -> Result<Pattern, PatternError> {
+    let mut pattern: Option<Pattern> = None;
+    let (mut name, mut author, mut comments) = (None, None, Vec::new());
+    let (mut x, mut y): (usize, usize) = (0, 0);
+    let mut count: Option<usize> = None;
+    let mut prefix: Option<u8> = None;
+    let mut number = 0;
+    for line in content.lines() {
+        number += 1;
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            let text = comment.get(1..).unwrap_or_default().trim().to_string();
+            match comment.chars().next() {
+                Some('N') => name = Some(text),
+                Some('O') => author = Some(text),
+                Some('C' | 'c') => comments.push(text),
+                _ => {}
+            }
+            continue;
+        }
+        let pattern = match &mut pattern {
+            Some(pattern) => pattern,
+            None if line.is_empty() => continue,
+            None => {
+                pattern = Some(parse_header(line, number)?);
+                continue;
+            }
+        };
+        let too_long = || parse_error(number, "the run is too long");
+        for c in line.chars() {
+            if prefix.is_some() && !c.is_ascii_uppercase() {
+                return Err(parse_error(
+                    number,
+                    format!("invalid character '{}' after a state prefix", c),
+                ));
+            }
+            let state = match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).unwrap() as usize;
+                    let run = count.unwrap_or(0).checked_mul(10);
+                    count = Some(
+                        run.and_then(|run| run.checked_add(digit))
+                            .ok_or_else(too_long)?,
+                    );
+                    continue;
+                }
+                'p'..='y' => {
+                    prefix = Some(c as u8 - b'p' + 1);
+                    continue;
+                }
+                '!' => {
+                    pattern.name = name;
+                    pattern.author = author;
+                    pattern.comments = comments;
+                    return Ok(pattern.clone());
+                }
+                '$' => {
+                    y = y
+                        .checked_add(count.take().unwrap_or(1))
+                        .ok_or_else(too_long)?;
+                    x = 0;
+                    continue;
+                }
+                c if c.is_whitespace() => continue,
+                'b' | '.' => 0,
+                'o' => 1,
+                'A'..='X' => {
+                    let state =
+                        prefix.take().unwrap_or(0) as usize * 24 + (c as u8 - b'A') as usize + 1;
+                    u8::try_from(state)
+                        .map_err(|_| parse_error(number, format!("state {} is above 255", state)))?
+                }
+                _ => return Err(parse_error(number, format!("invalid character '{}'", c))),
+            };
+            let run = count.take().unwrap_or(1);
+            let end = x.checked_add(run).ok_or_else(too_long)?;
+            if state != 0 {
+                let (width, height) = pattern.dim();
+                if end > width || y >= height {
+                    return Err(parse_error(
+                        number,
+                        format!(
+                            "the cells exceed the size x = {}, y = {} of the header",
+                            width, height
+                        ),
+                    ));
+                }
+                for x in x..end {
+                    pattern.set(x, y, state);
+                }
+            }
+            x = end;
+        }
+    }
+    Err(parse_error(number, "the pattern does not end with '!'"))
+}
+
+/// Parse the header line `x = 3, y = 3, rule = B3/S23`, of which the rule is optional.
+/// Sizes above `MAX_CELLS` cells are rejected before the pattern is allocated.
+fn parse_header(line: &str, number: usize) -> Result<Pattern, PatternError> {
+    let (mut width, mut height, mut rule) = (None, None, None);
+    let mut rest = line;
+    while !rest.trim().is_empty() {
+        let (key, value) = rest.split_once('=').ok_or_else(|| {
+            parse_error(number, "expected a header like x = 3, y = 3, rule = B3/S23")
+        })?;
+        // The rule is the last entry and may contain commas, as in the bounded grid B3/S23:T100,100
+        if key.trim() == "rule" {
+            rule = Some(value.trim().to_string());
+            break;
+        }
+        let (value, next) = value.split_once(',').unwrap_or((value, ""));
+        rest = next;
+        let value = value.trim();
+        let size = || {
+            value
+                .parse::<usize>()
+                .map_err(|_| parse_error(number, format!("invalid size {}", value)))
+        };
+        match key.trim() {
+            "x" => width = Some(size()?),
+            "y" => height = Some(size()?),
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => Ok(Pattern {
+            rule,
+            ..Pattern::checked_new(width, height)?
+        }),
+        _ => Err(parse_error(
+            number,
+            "the header has to give the size as x = and y =",
+        )),
+    }
+}
+
+/// Write the pattern in the run length encoded format.
+/// Patterns with states above 1 are written with the letters of multi-state patterns.
+pub fn write(pattern: &Pattern) -> String {
+    let mut rle = String::new();
+    for (tag, text) in [("N", &pattern.name), ("O", &pattern.author)] {
+        if let Some(text) = text {
+            rle += &format!("#{} {}\n", tag, text);
+        }
+    }
+    for comment in &pattern.comments {
+        rle += &format!("#C {}\n", comment);
+    }
+    let (width, height) = pattern.dim();
+    rle += &format!("x = {}, y = {}", width, height);
+    if let Some(rule) = &pattern.rule {
+        rle += &format!(", rule = {}", rule);
+    }
+    rle.push('\n');
+
+    let multi_state = pattern.max_state() > 1;
+    let mut line = String::new();
+    let mut push = |run: usize, tag: String, rle: &mut String| {
+        let token = if run > 1 {
+            format!("{}{}", run, tag)
+        } else {
+            tag
+        };
+        if line.len() + token.len() > MAX_LINE_LENGTH {
+            *rle += &line;
+            rle.push('\n');
+            line.clear();
+        }
+        line += &token;
+    };
+    let mut row_ends = 0;
+    for y in 0..height {
+        let row: Vec<u8> = (0..width).map(|x| pattern.get(x, y)).collect();
+        // Dead cells at the end of a row are left out
+        let length = row
+            .iter()
+            .rposition(|&state| state != 0)
+            .map_or(0, |last| last + 1);
+        if length == 0 {
+            row_ends += 1;
+            continue;
+        }
+        if row_ends > 0 {
+            push(row_ends, "$".to_string(), &mut rle);
+        }
+        let mut x = 0;
+        while x < length {
+            let state = row[x];
+            let run = row[x..length].iter().take_while(|&&s| s == state).count();
+            push(run, state_tag(state, multi_state), &mut rle);
+            x += run;
+        }
+        row_ends = 1;
+    }
+    push(1, "!".to_string(), &mut rle);
+    rle + &line + "\n"
+}
+
+/// The letters standing for the state
+fn state_tag(state: u8, multi_state: bool) -> String {
+    match (state, multi_state) {
+        (0, false) => "b".to_string(),
+        (_, false) => "o".to_string(),
+        (0, true) => ".".to_string(),
+        (1..=24, true) => char::from(b'A' + state - 1).to_string(),
+        (_, true) => {
+            let (prefix, letter) = ((state - 25) / 24, (state - 25) % 24);
+            format!("{}{}", char::from(b'p' + prefix), char::from(b'A' + letter))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+    use crate::game_board::*;
+
+    const GLIDER: &str = "#N Glider
+#O Richard K. Guy
+#C The smallest spaceship.
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+";
+
+    #[test]
+    fn test_rle_round_trip() {
+        let glider = parse(GLIDER).unwrap();
+        assert_eq!(glider.dim(), (3, 3));
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(write(&glider), GLIDER);
+
+        let mut multi_state = Pattern::new(30, 2);
+        for (x, state) in [(0, 1), (1, 1), (5, 24), (6, 25), (29, 255)] {
+            multi_state.set(x, 1, state);
+        }
+        let rle = write(&multi_state);
+        assert_eq!(rle, "x = 30, y = 2\n$2A3.XpA22.yO!\n");
+        assert_eq!(parse(&rle).unwrap(), multi_state);
+
+        // Rules of bounded grids contain a comma, which belongs to the rule
+        let torus = parse("x = 3, y = 1, rule = B3/S23:T100,100\n3o!").unwrap();
+        assert_eq!(torus.rule.as_deref(), Some("B3/S23:T100,100"));
+        assert_eq!((torus.dim(), torus.get(2, 0)), ((3, 1), 1));
+    }
+
+    #[test]
+    fn test_rle_stamp() {
+        let glider = parse(GLIDER).unwrap();
+        let mut board = GameBoard::new(10, 10, CellConway::Dead);
+        glider.stamp(&mut board, 8, 1).unwrap();
+        let alive: Vec<_> = (0..100)
+            .map(|i| (i % 10, i / 10))
+            .filter(|&(x, y)| *board.get(x, y).unwrap() == CellConway::Alive)
+            .collect();
+        assert_eq!(alive, [(9, 1), (8, 3), (9, 3)]);
+        let region = Pattern::from_board(&board, 7, 1, 3, 3);
+        assert_eq!(write(&region), "x = 3, y = 3\n2bo2$b2o!\n");
+    }
+
+    #[test]
+    fn test_rle_errors_name_the_line() {
+        let error = parse("#C comment\nx = 2, y = 2\no$2o$o!").unwrap_err();
+        assert!(
+            matches!(error, PatternError::Parse { line: 3, .. }),
+            "{}",
+            error
+        );
+        let error = parse("x = 2, y = 2\n2o\n$2q!").unwrap_err();
+        assert!(
+            matches!(error, PatternError::Parse { line: 3, .. }),
+            "{}",
+            error
+        );
+        // Runs and sizes which overflow are rejected instead of allocating or wrapping around
+        let error = parse("x = 2, y = 2\n99999999999999999999o!").unwrap_err();
+        assert!(
+            matches!(error, PatternError::Parse { line: 2, .. }),
+            "{}",
+            error
+        );
+        let error = parse("x = 2, y = 2\no18446744073709551615o!").unwrap_err();
+        assert!(
+            matches!(error, PatternError::Parse { line: 2, .. }),
+            "{}",
+            error
+        );
+        for header in ["x = 100000, y = 100000", "x = 4294967296, y = 4294967296"] {
+            assert!(matches!(
+                parse(&format!("{}\no!", header)),
+                Err(PatternError::TooLarge { .. })
+            ));
+        }
+    }
+}
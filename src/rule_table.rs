@@ -0,0 +1,688 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::cell::*;
+use crate::game_board::*;
+use crate::game_rules::*;
+use crate::rgba::RGBA;
+
+/// The most transitions a rule table may expand to, before the file is rejected as too large.
+const MAX_TRANSITIONS: usize = 1 << 24;
+
+/// A cell of a rule table, identified by its state number.
+///
+/// The cell only knows its state and the number of states, so it is drawn in Golly's default colors.
+/// The colors given in the `@COLORS` section of the file are kept by the table, see `RuleTable::color`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TableCell {
+    state: u8,
+    states: u16,
+}
+
+impl TableCell {
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+}
+
+impl Cell for TableCell {
+    fn to_rgba(&self) -> RGBA {
+        RGBA(default_color(self.state as usize, self.states as usize))
+    }
+    fn next(&self) -> Self {
+        Self {
+            state: ((self.state as u16 + 1) % self.states) as u8,
+            states: self.states,
+        }
+    }
+}
+
+/// The symmetries of a rule table. A transition applies to every arrangement of the neighbors it is mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableSymmetry {
+    None,
+    /// Rotations by 90 degrees
+    Rotate4,
+    /// Rotations by 45 degrees, only for the Moore neighborhood
+    Rotate8,
+    /// Mirroring at the vertical axis
+    ReflectHorizontal,
+    Rotate4Reflect,
+    Rotate8Reflect,
+    /// Any arrangement of the neighbors, so only the number of neighbors in each state matters
+    Permute,
+}
+
+/// The reasons a rule table cannot be loaded
+#[derive(Debug)]
+pub enum TableError {
+    /// The file cannot be read
+    Io(std::io::Error),
+    /// The content of the given line, counting from 1, is malformed
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot read the rule table: {}", e),
+            Self::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for TableError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TableError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> TableError {
+    TableError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+/// A rule given as a Golly rule table, mapping the states of a cell and its neighbors to the next state.
+/// Cells without a matching transition keep their state.
+///
+/// Loading a table expands its variables and symmetries into a lookup table of all transitions.
+/// The rule is loaded at runtime, so it is a ContextGameRule rather than a GameRule.
+#[derive(Debug, Clone)]
+pub struct RuleTable {
+    name: String,
+    neighborhood: NeighborhoodShape,
+    symmetry: TableSymmetry,
+    transitions: Arc<HashMap<[u8; 9], u8>>,
+    colors: Arc<[[u8; 4]]>,
+}
+
+impl Default for RuleTable {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            neighborhood: NeighborhoodShape::Moore(1),
+            symmetry: TableSymmetry::None,
+            transitions: Arc::default(),
+            colors: (0..2).map(|state| default_color(state, 2)).collect(),
+        }
+    }
+}
+
+impl RuleTable {
+    /// Load a `.table` file, or a `.rule` file containing a `@TABLE` and optionally a `@COLORS` section.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TableError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+    /// Parse the content of a `.table` or `.rule` file. Tables can have up to 256 states.
+    pub fn parse(content: &str) -> Result<Self, TableError> {
+        let lines: Vec<(usize, &str)> = content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split('#').next().unwrap().trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        let has_sections = lines.iter().any(|(_, line)| line.starts_with('@'));
+        let (mut name, mut table, mut colors) = (String::new(), Vec::new(), Vec::new());
+        let mut section = if has_sections { "" } else { "@TABLE" };
+        for &(number, line) in &lines {
+            if line.starts_with('@') {
+                let mut words = line.split_whitespace();
+                section = words.next().unwrap();
+                if section == "@RULE" {
+                    name = words.next().unwrap_or_default().to_string();
+                }
+                continue;
+            }
+            match section {
+                "@TABLE" => table.push((number, line)),
+                "@COLORS" => colors.push((number, line)),
+                _ => {}
+            }
+        }
+        if table.is_empty() {
+            let last_line = lines.last().map_or(1, |&(number, _)| number);
+            return Err(parse_error(last_line, "the file contains no rule table"));
+        }
+        let (mut rule, states) = parse_table(&table)?;
+        rule.name = name;
+        rule.colors = parse_colors(&colors, states)?.into();
+        Ok(rule)
+    }
+    /// Get the name given after `@RULE`, which is empty for plain `.table` files
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Get the number of states
+    pub fn states(&self) -> usize {
+        self.colors.len()
+    }
+    /// Get the cell of this rule in the given state. Panics if the rule has no such state.
+    pub fn cell(&self, state: u8) -> TableCell {
+        assert!(
+            (state as usize) < self.states(),
+            "The rule table has no state {}!",
+            state
+        );
+        TableCell {
+            state,
+            states: self.colors.len() as u16,
+        }
+    }
+    /// Get the color of the cell given in the `@COLORS` section, or Golly's default color if there is none
+    pub fn color(&self, cell: &TableCell) -> RGBA {
+        RGBA(self.colors[cell.state as usize])
+    }
+    /// Get the colors of all states, in the form `image::read_png` expects
+    pub fn palette(&self) -> Vec<(RGBA, TableCell)> {
+        (0..self.states())
+            .map(|state| self.cell(state as u8))
+            .map(|cell| (self.color(&cell), cell))
+            .collect()
+    }
+    /// Get the number of the state the cell becomes with the given neighbors,
+    /// which are ordered clockwise starting at the top.
+    pub fn next_state(&self, cell: u8, neighbors: &[u8]) -> u8 {
+        let mut key = [0; 9];
+        key[0] = cell;
+        key[1..=neighbors.len()].copy_from_slice(neighbors);
+        if self.symmetry == TableSymmetry::Permute {
+            key[1..=neighbors.len()].sort_unstable();
+        }
+        *self.transitions.get(&key).unwrap_or(&cell)
+    }
+}
+
+impl ContextGameRule for RuleTable {
+    type Cell = TableCell;
+    fn apply_in_context(
+        &self,
+        cell: &Self::Cell,
+        neighbors: Neighborhood<Self::Cell>,
+        _context: &mut RuleContext,
+    ) -> Self::Cell {
+        let directions = table_directions(&self.neighborhood);
+        let mut states = [0; 8];
+        for (state, &direction) in states.iter_mut().zip(directions) {
+            *state = neighbors[direction].state;
+        }
+        TableCell {
+            state: self.next_state(cell.state, &states[..directions.len()]),
+            states: cell.states,
+        }
+    }
+    fn neighborhood(&self) -> NeighborhoodShape {
+        self.neighborhood.clone()
+    }
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+/// The directions of the neighbors in the order of the transitions, clockwise starting at the top
+fn table_directions(neighborhood: &NeighborhoodShape) -> &'static [Direction] {
+    match neighborhood {
+        NeighborhoodShape::VonNeumann(_) => {
+            &[Direction::N, Direction::E, Direction::S, Direction::W]
+        }
+        _ => &Direction::ALL,
+    }
+}
+
+/// An entry of a transition, either a fixed state or the index of a variable
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Entry {
+    State(u8),
+    Variable(usize),
+}
+
+fn parse_table(lines: &[(usize, &str)]) -> Result<(RuleTable, usize), TableError> {
+    let mut states = None;
+    let mut neighborhood = None;
+    let mut symmetry = None;
+    let mut variables: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut transitions = HashMap::new();
+    for &(number, line) in lines {
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "n_states" => match value.parse::<usize>() {
+                    Ok(n) if (2..=256).contains(&n) => states = Some(n),
+                    _ => return Err(parse_error(number, "n_states has to be between 2 and 256")),
+                },
+                "neighborhood" => {
+                    neighborhood = Some(match value {
+                        "Moore" => NeighborhoodShape::Moore(1),
+                        "vonNeumann" => NeighborhoodShape::VonNeumann(1),
+                        _ => {
+                            return Err(parse_error(
+                                number,
+                                format!("unsupported neighborhood {}", value),
+                            ))
+                        }
+                    })
+                }
+                "symmetries" => {
+                    symmetry = Some(match value {
+                        "none" => TableSymmetry::None,
+                        "rotate4" => TableSymmetry::Rotate4,
+                        "rotate8" => TableSymmetry::Rotate8,
+                        "reflect_horizontal" => TableSymmetry::ReflectHorizontal,
+                        "rotate4reflect" => TableSymmetry::Rotate4Reflect,
+                        "rotate8reflect" => TableSymmetry::Rotate8Reflect,
+                        "permute" => TableSymmetry::Permute,
+                        _ => {
+                            return Err(parse_error(
+                                number,
+                                format!("unsupported symmetries {}", value),
+                            ))
+                        }
+                    })
+                }
+                key => return Err(parse_error(number, format!("unknown setting {}", key))),
+            }
+            continue;
+        }
+        let (states, neighborhood, symmetry) = match (states, &neighborhood, symmetry) {
+            (Some(states), Some(neighborhood), Some(symmetry)) => (states, neighborhood, symmetry),
+            _ => {
+                return Err(parse_error(
+                    number,
+                    "n_states, neighborhood and symmetries have to be given before the first variable or transition",
+                ))
+            }
+        };
+        if matches!(neighborhood, NeighborhoodShape::VonNeumann(_))
+            && matches!(
+                symmetry,
+                TableSymmetry::Rotate8 | TableSymmetry::Rotate8Reflect
+            )
+        {
+            return Err(parse_error(
+                number,
+                "rotate8 symmetries are only supported for the Moore neighborhood",
+            ));
+        }
+        let parse_state = |name: &str| -> Result<u8, TableError> {
+            match name.parse::<u8>() {
+                Ok(state) if (state as usize) < states => Ok(state),
+                _ => Err(parse_error(
+                    number,
+                    format!("{} is neither a state nor a variable", name),
+                )),
+            }
+        };
+        if let Some(declaration) = line.strip_prefix("var ") {
+            let (name, values) = declaration.split_once('=').ok_or_else(|| {
+                parse_error(number, "a variable has to be declared as var name={0,1}")
+            })?;
+            let values = values
+                .trim()
+                .strip_prefix('{')
+                .and_then(|values| values.strip_suffix('}'))
+                .ok_or_else(|| {
+                    parse_error(
+                        number,
+                        "the values of a variable have to be enclosed in braces",
+                    )
+                })?;
+            let mut set = Vec::new();
+            for value in values.split(',').map(str::trim) {
+                match variables.iter().find(|(name, _)| name == value) {
+                    Some((_, values)) => set.extend_from_slice(values),
+                    None => set.push(parse_state(value)?),
+                }
+            }
+            variables.push((name.trim().to_string(), set));
+            continue;
+        }
+        let entries: Vec<&str> = if line.contains(',') {
+            line.split(',').map(str::trim).collect()
+        } else {
+            // Tables with at most 10 states may write transitions without commas
+            line.char_indices()
+                .map(|(i, c)| &line[i..i + c.len_utf8()])
+                .collect()
+        };
+        let neighbors = table_directions(neighborhood).len();
+        if entries.len() != neighbors + 2 {
+            return Err(parse_error(
+                number,
+                format!(
+                    "a transition needs {} entries, found {}",
+                    neighbors + 2,
+                    entries.len()
+                ),
+            ));
+        }
+        let entries = entries
+            .iter()
+            .map(
+                |&name| match variables.iter().rposition(|(variable, _)| variable == name) {
+                    Some(i) => Ok(Entry::Variable(i)),
+                    None => parse_state(name).map(Entry::State),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        let (output, inputs) = entries.split_last().unwrap();
+        if let Entry::Variable(i) = output {
+            if !inputs.contains(output) {
+                return Err(parse_error(
+                    number,
+                    format!(
+                        "the output variable {} does not occur in the inputs",
+                        variables[*i].0
+                    ),
+                ));
+            }
+        }
+        expand_transition(inputs, *output, &variables, symmetry, &mut transitions)
+            .ok_or_else(|| parse_error(number, "the rule table expands to too many transitions"))?;
+    }
+    let states =
+        states.ok_or_else(|| parse_error(lines.last().unwrap().0, "n_states is missing"))?;
+    let rule = RuleTable {
+        neighborhood: neighborhood.unwrap_or_default(),
+        symmetry: symmetry.unwrap_or(TableSymmetry::None),
+        transitions: Arc::new(transitions),
+        ..RuleTable::default()
+    };
+    Ok((rule, states))
+}
+
+/// Insert every transition described by the entries into the lookup table, binding each variable to every
+/// one of its values. Transitions given earlier take precedence, so existing entries are kept.
+/// Returns None without inserting anything if the table could exceed `MAX_TRANSITIONS` transitions with them.
+fn expand_transition(
+    inputs: &[Entry],
+    output: Entry,
+    variables: &[(String, Vec<u8>)],
+    symmetry: TableSymmetry,
+    transitions: &mut HashMap<[u8; 9], u8>,
+) -> Option<()> {
+    let mut bound: Vec<usize> = inputs
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::Variable(i) => Some(*i),
+            Entry::State(_) => None,
+        })
+        .collect();
+    bound.sort_unstable();
+    bound.dedup();
+    let arrangements = symmetric_arrangements(inputs.len() - 1, symmetry);
+    // Checked before expanding, since a few free variables over many states already make up billions of bindings
+    bound
+        .iter()
+        .try_fold(arrangements.len(), |count, &i| {
+            count.checked_mul(variables[i].1.len())
+        })
+        .filter(|&count| transitions.len() + count <= MAX_TRANSITIONS)?;
+    // Each variable is bound to a single value throughout the transition
+    let mut choice = vec![0; bound.len()];
+    loop {
+        let value = |entry: Entry| match entry {
+            Entry::State(state) => state,
+            Entry::Variable(i) => {
+                let k = bound.binary_search(&i).unwrap();
+                variables[i].1[choice[k]]
+            }
+        };
+        let next = value(output);
+        for arrangement in &arrangements {
+            let mut key = [0; 9];
+            key[0] = value(inputs[0]);
+            for (j, &from) in arrangement.iter().enumerate() {
+                key[j + 1] = value(inputs[from + 1]);
+            }
+            if symmetry == TableSymmetry::Permute {
+                key[1..inputs.len()].sort_unstable();
+            }
+            transitions.entry(key).or_insert(next);
+        }
+        // Advance the choice of values like the digits of a number
+        let carry = choice.iter_mut().zip(&bound).all(|(k, &i)| {
+            *k += 1;
+            if *k == variables[i].1.len() {
+                *k = 0;
+                true
+            } else {
+                false
+            }
+        });
+        if carry {
+            return Some(());
+        }
+    }
+}
+
+/// The arrangements of the neighbors the symmetry maps a transition to, as the index of the neighbor
+/// placed at each position. The neighbors are ordered clockwise, so a rotation shifts the indices.
+fn symmetric_arrangements(neighbors: usize, symmetry: TableSymmetry) -> Vec<Vec<usize>> {
+    let (step, reflect) = match symmetry {
+        // All permutations lead to the same sorted key
+        TableSymmetry::None | TableSymmetry::Permute => (neighbors, false),
+        TableSymmetry::Rotate4 => (neighbors / 4, false),
+        TableSymmetry::Rotate8 => (1, false),
+        TableSymmetry::ReflectHorizontal => (neighbors, true),
+        TableSymmetry::Rotate4Reflect => (neighbors / 4, true),
+        TableSymmetry::Rotate8Reflect => (1, true),
+    };
+    let mut arrangements = Vec::new();
+    for rotation in (0..neighbors).step_by(step) {
+        arrangements.push((0..neighbors).map(|j| (j + rotation) % neighbors).collect());
+        if reflect {
+            arrangements.push(
+                (0..neighbors)
+                    .map(|j| (neighbors - j + rotation) % neighbors)
+                    .collect(),
+            );
+        }
+    }
+    arrangements
+}
+
+/// The color of a state in Golly's default palette: black for state 0 and a gradient from red to yellow for the others
+fn default_color(state: usize, states: usize) -> [u8; 4] {
+    match state {
+        0 => [0x00, 0x00, 0x00, 0xFF],
+        _ => gradient_color(state, states, [0xFF, 0x00, 0x00], [0xFF, 0xFF, 0x00]),
+    }
+}
+
+/// The color of a state on a gradient spanning all states but the first
+fn gradient_color(state: usize, states: usize, from: [u8; 3], to: [u8; 3]) -> [u8; 4] {
+    let t = if states > 2 {
+        (state - 1) as f32 / (states - 2) as f32
+    } else {
+        0.0
+    };
+    let mut color = [0xFF; 4];
+    for channel in 0..3 {
+        color[channel] = (from[channel] as f32 * (1.0 - t) + to[channel] as f32 * t) as u8;
+    }
+    color
+}
+
+/// Parse the `@COLORS` section, with lines of either `state r g b` or a gradient `r1 g1 b1 r2 g2 b2`
+/// spanning all states but the first. States without a color are black, except for Golly's default gradient.
+fn parse_colors(lines: &[(usize, &str)], states: usize) -> Result<Vec<[u8; 4]>, TableError> {
+    let gradient = |colors: &mut Vec<[u8; 4]>, from: [u8; 3], to: [u8; 3]| {
+        for (state, color) in colors.iter_mut().enumerate().skip(1) {
+            *color = gradient_color(state, states, from, to);
+        }
+    };
+    let mut colors: Vec<_> = (0..states)
+        .map(|state| default_color(state, states))
+        .collect();
+    for &(number, line) in lines {
+        let values = line
+            .split_whitespace()
+            .map(|value| value.parse::<u8>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| {
+                parse_error(
+                    number,
+                    "colors have to be given as numbers between 0 and 255",
+                )
+            })?;
+        match values[..] {
+            [state, r, g, b] if (state as usize) < states => {
+                colors[state as usize] = [r, g, b, 0xFF]
+            }
+            [state, ..] if values.len() == 4 => {
+                return Err(parse_error(
+                    number,
+                    format!("the rule table has no state {}", state),
+                ))
+            }
+            [r1, g1, b1, r2, g2, b2] => gradient(&mut colors, [r1, g1, b1], [r2, g2, b2]),
+            _ => {
+                return Err(parse_error(
+                    number,
+                    "expected either state r g b or r1 g1 b1 r2 g2 b2",
+                ))
+            }
+        }
+    }
+    Ok(colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+    use crate::game::{traits::CellGame as CellGameTrait, CellGame};
+
+    const LIFE_RULE: &str = "@RULE Life
+@TABLE
+n_states:2
+neighborhood:Moore
+symmetries:permute
+var a={0,1}
+var b={a}
+var c={a}
+var d={a}
+var e={a}
+var f={a}
+var g={a}
+var h={a}
+0,1,1,1,0,0,0,0,0,1
+1,1,1,0,0,0,0,0,0,1
+1,1,1,1,0,0,0,0,0,1
+1,a,b,c,d,e,f,g,h,0
+@COLORS
+1 92 203 203
+";
+
+    #[test]
+    fn test_rule_table_plays_life() {
+        let table = RuleTable::parse(LIFE_RULE).unwrap();
+        assert_eq!(table.name(), "Life");
+        assert_eq!(table.color(&table.cell(1)).get_raw(), [92, 203, 203, 255]);
+        let conway: CellGame<CellConway, ConwayRule> =
+            CellGame::from_board(GameBoard::new_rand_seeded(40, 30, Boundary::Toroidal, 3));
+        let board = conway.get_board();
+        let mut cells = GameBoard::with_boundary(40, 30, table.cell(0), Boundary::Toroidal);
+        for (cell, conway_cell) in cells.iter_mut().zip(board.iter()) {
+            *cell = table.cell((*conway_cell == CellConway::Alive) as u8);
+        }
+        cells.update_border();
+        let (mut conway, mut game) = (conway, CellGame::with_rule(cells, table));
+        for _ in 0..10 {
+            conway.step();
+            game.step();
+        }
+        for (cell, conway_cell) in game.get_board().iter().zip(conway.get_board().iter()) {
+            assert_eq!(cell.state() == 1, *conway_cell == CellConway::Alive);
+        }
+    }
+
+    #[test]
+    fn test_rule_table_symmetries() {
+        let table = RuleTable::parse(
+            "n_states:3\nneighborhood:vonNeumann\nsymmetries:rotate4reflect\n# a comment\n012002\n",
+        )
+        .unwrap();
+        for neighbors in [[1, 2, 0, 0], [0, 0, 1, 2], [0, 0, 2, 1], [2, 1, 0, 0]] {
+            assert_eq!(table.next_state(0, &neighbors), 2);
+        }
+        assert_eq!(table.next_state(0, &[1, 0, 2, 0]), 0);
+    }
+
+    #[test]
+    fn test_rule_table_rejects_huge_expansions() {
+        // 30^9 bindings of the free variables, which must be rejected before they are enumerated
+        let mut table = String::from("n_states:30\nneighborhood:Moore\nsymmetries:rotate8\n");
+        table += "var a={0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27,28,29}\n";
+        for name in ["b", "c", "d", "e", "f", "g", "h", "i"] {
+            table += &format!("var {}={{a}}\n", name);
+        }
+        table += "a,b,c,d,e,f,g,h,i,a\n";
+        let error = RuleTable::parse(&table).unwrap_err();
+        assert!(
+            matches!(error, TableError::Parse { line: 13, .. }),
+            "{}",
+            error
+        );
+        let error =
+            RuleTable::parse("n_states:2\nneighborhood:vonNeumann\nsymmetries:rotate8\n000000\n")
+                .unwrap_err();
+        assert!(
+            matches!(error, TableError::Parse { line: 4, .. }),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_rule_table_palettes() {
+        let table = RuleTable::parse(LIFE_RULE).unwrap();
+        // The colors of the file belong to the table, while the cells draw the default colors
+        let palette: Vec<_> = table
+            .palette()
+            .iter()
+            .map(|(rgba, _)| rgba.get_raw())
+            .collect();
+        assert_eq!(palette, [[0, 0, 0, 255], [92, 203, 203, 255]]);
+        assert_eq!(table.cell(1).to_rgba().get_raw(), [255, 0, 0, 255]);
+        assert_eq!(RuleTable::default().cell(1), table.cell(1));
+        let mut states = String::from("n_states:256\nneighborhood:vonNeumann\nsymmetries:none\n");
+        states += "255,0,0,0,0,1\n";
+        let table = RuleTable::parse(&states).unwrap();
+        assert_eq!(table.states(), 256);
+        assert_eq!(table.next_state(255, &[0, 0, 0, 0]), 1);
+        assert_eq!(table.cell(255).next(), table.cell(0));
+    }
+
+    #[test]
+    fn test_rule_table_errors_name_the_line() {
+        let error = RuleTable::parse("n_states:2\nneighborhood:Moore\nsymmetries:none\n\n0,1,2\n")
+            .unwrap_err();
+        assert!(
+            matches!(error, TableError::Parse { line: 5, .. }),
+            "{}",
+            error
+        );
+        let error = RuleTable::parse(
+            "n_states:2\nneighborhood:Moore\nsymmetries:none\n0,1,1,1,0,0,0,0,0,3\n",
+        )
+        .unwrap_err();
+        assert!(
+            matches!(error, TableError::Parse { line: 4, .. }),
+            "{}",
+            error
+        );
+    }
+}
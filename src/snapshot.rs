@@ -0,0 +1,336 @@
+use std::fmt;
+use std::path::Path;
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::cell::Cell;
+use crate::game::traits::CellGame;
+use crate::game_board::*;
+
+/// Marks the data as a snapshot of this crate
+const FORMAT: &str = "cell-engine-rs snapshot";
+/// The version of the snapshot format written by this crate. Snapshots of other versions are rejected.
+pub const VERSION: u32 = 1;
+
+/// The state of a GameBoard, or of a game together with its generation and seed, which can be
+/// written to and read from JSON or a compact binary format.
+///
+/// Only the inner cells are stored, the border is rebuilt from the boundary when the board is restored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot<C: Cell> {
+    format: String,
+    version: u32,
+    width: usize,
+    height: usize,
+    neighborhood: NeighborhoodShape,
+    boundary: Boundary<C>,
+    generation: u64,
+    seed: Option<u64>,
+    cells: Vec<C>,
+}
+
+/// The start of every snapshot, which is read first to reject unknown data before the rest is decoded
+#[derive(Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+}
+
+/// The reasons a snapshot cannot be read
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The file cannot be read or written
+    Io(std::io::Error),
+    /// The JSON is malformed or does not describe a snapshot
+    Json(serde_json::Error),
+    /// The binary data is malformed or does not describe a snapshot
+    Binary(bincode::Error),
+    /// The data is not a snapshot of this crate
+    UnknownFormat,
+    /// The snapshot was written in another version of the format
+    UnsupportedVersion(u32),
+    /// The dimension of the board is empty or does not match the number of stored cells
+    InvalidDimension {
+        width: usize,
+        height: usize,
+        cells: usize,
+    },
+    /// The neighborhood reaches so far that the border around the board would hold
+    /// more than 8 times as many cells as the board itself
+    InvalidNeighborhood { radius: usize },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot access the snapshot file: {}", e),
+            Self::Json(e) => write!(f, "invalid snapshot JSON: {}", e),
+            Self::Binary(e) => write!(f, "invalid binary snapshot: {}", e),
+            Self::UnknownFormat => write!(f, "the data is not a snapshot"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "snapshot version {} is not supported, only version {}",
+                version, VERSION
+            ),
+            Self::InvalidDimension {
+                width,
+                height,
+                cells,
+            } => write!(
+                f,
+                "a board of {}x{} cells cannot hold {} cells",
+                width, height, cells
+            ),
+            Self::InvalidNeighborhood { radius } => write!(
+                f,
+                "a neighborhood of radius {} is too large for the board",
+                radius
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::Binary(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(e: bincode::Error) -> Self {
+        Self::Binary(e)
+    }
+}
+
+/// Variable length integers keep the binary format compact, since most cells are enums with few variants
+fn binary_options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+impl<C: Cell + Serialize + DeserializeOwned> Snapshot<C> {
+    /// Take a snapshot of the cells, neighborhood and boundary of the board
+    pub fn of_board(board: &GameBoard<C>) -> Self {
+        let (width, height) = board.dim();
+        Self {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            width,
+            height,
+            neighborhood: board.neighborhood().clone(),
+            boundary: *board.boundary(),
+            generation: 0,
+            seed: None,
+            cells: board.iter().copied().collect(),
+        }
+    }
+    /// Take a snapshot of the board of the game together with its generation and seed.
+    /// The board is brought up to date first, since some games only write their cells into it on `update_board`.
+    pub fn of_game<G: CellGame<Cell = C>>(game: &mut G) -> Self {
+        game.update_board();
+        Self {
+            generation: game.generation(),
+            seed: game.seed(),
+            ..Self::of_board(game.get_board())
+        }
+    }
+    /// Get the dimension of the stored board
+    pub fn dim(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    /// Get the generation of the game the snapshot was taken of, or 0 for a board
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    /// Get the seed of the game the snapshot was taken of, if it has one
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+    /// Construct a GameBoard with the stored cells, neighborhood and boundary
+    pub fn to_board(&self) -> GameBoard<C> {
+        let mut board =
+            GameBoard::with_boundary(self.width, self.height, self.cells[0], self.boundary);
+        board.set_neighborhood(self.neighborhood.clone());
+        board
+            .iter_mut()
+            .zip(&self.cells)
+            .for_each(|(cell, stored)| *cell = *stored);
+        board.update_border();
+        board
+    }
+    /// Write the snapshot as JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Snapshots only contain JSON compatible data")
+    }
+    /// Read a snapshot from JSON
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        check_header(serde_json::from_str(json)?)?;
+        serde_json::from_str::<Self>(json)?.validated()
+    }
+    /// Write the snapshot in the binary format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        binary_options()
+            .serialize(self)
+            .expect("Snapshots can always be serialized")
+    }
+    /// Read a snapshot from the binary format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        check_header(binary_options().allow_trailing_bytes().deserialize(bytes)?)?;
+        binary_options().deserialize::<Self>(bytes)?.validated()
+    }
+    /// Write the snapshot to a file, as JSON if the extension is `json` and in the binary format otherwise
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        if is_json(path) {
+            std::fs::write(path, self.to_json())?;
+        } else {
+            std::fs::write(path, self.to_bytes())?;
+        }
+        Ok(())
+    }
+    /// Read a snapshot from a file, as JSON if the extension is `json` and in the binary format otherwise
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let path = path.as_ref();
+        if is_json(path) {
+            Self::from_json(&std::fs::read_to_string(path)?)
+        } else {
+            Self::from_bytes(&std::fs::read(path)?)
+        }
+    }
+    fn validated(self) -> Result<Self, SnapshotError> {
+        let (width, height, cells) = (self.width, self.height, self.cells.len());
+        if cells == 0 || width.checked_mul(height) != Some(cells) {
+            return Err(SnapshotError::InvalidDimension {
+                width,
+                height,
+                cells,
+            });
+        }
+        // The board allocates a border as thick as the radius, which must not dwarf the board
+        let radius = self.neighborhood.radius();
+        let outer_side = |side: usize| {
+            radius
+                .checked_mul(2)
+                .and_then(|border| border.checked_add(side))
+        };
+        let outer_cells = outer_side(width)
+            .zip(outer_side(height))
+            .and_then(|(w, h)| w.checked_mul(h));
+        if outer_cells.is_none_or(|outer_cells| outer_cells > cells.saturating_mul(9)) {
+            return Err(SnapshotError::InvalidNeighborhood { radius });
+        }
+        Ok(self)
+    }
+}
+
+fn check_header(header: Header) -> Result<(), SnapshotError> {
+    if header.format != FORMAT {
+        return Err(SnapshotError::UnknownFormat);
+    }
+    if header.version != VERSION {
+        return Err(SnapshotError::UnsupportedVersion(header.version));
+    }
+    Ok(())
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bit_game::BitGame;
+    use crate::cell::mock::*;
+    use crate::game::traits::RandCellGame;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        type Game = crate::game::CellGame<CellConway, ConwayRule>;
+        let mut game = Game::new_rand_seeded(12, 7, Boundary::Toroidal, 42);
+        game.step();
+        let snapshot = Snapshot::of_game(&mut game);
+        assert_eq!(Snapshot::from_json(&snapshot.to_json()).unwrap(), snapshot);
+        let restored = Snapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+        assert_eq!((restored.generation(), restored.seed()), (1, Some(42)));
+        let mut continued = Game::from_snapshot(&restored, ConwayRule {});
+        continued.step();
+        game.step();
+        assert!(continued.get_board().iter().eq(game.get_board().iter()));
+        assert_eq!(continued.get_board().boundary(), &Boundary::Toroidal);
+    }
+
+    #[test]
+    fn test_snapshot_of_stepped_bit_game() {
+        let boundary = Boundary::Toroidal;
+        let mut bits = BitGame::<CellConway>::new_rand_seeded(20, 10, boundary, 7);
+        let mut dense =
+            crate::game::CellGame::<CellConway, ConwayRule>::new_rand_seeded(20, 10, boundary, 7);
+        for _ in 0..3 {
+            bits.step();
+            dense.step();
+        }
+        // The view of the BitGame is only written on update_board, which the snapshot has to take care of
+        let snapshot = Snapshot::of_game(&mut bits);
+        assert_eq!(snapshot.generation(), 3);
+        assert!(snapshot.to_board().iter().eq(dense.get_board().iter()));
+    }
+
+    #[test]
+    fn test_snapshot_errors() {
+        let board = GameBoard::new(3, 2, CellConway::Dead);
+        let json = Snapshot::of_board(&board).to_json();
+        let missing_cell = json.replace(",\"Dead\"]", "]");
+        assert!(matches!(
+            Snapshot::<CellConway>::from_json(&missing_cell),
+            Err(SnapshotError::InvalidDimension { cells: 5, .. })
+        ));
+        let future = json.replace("\"version\":1", "\"version\":2");
+        assert!(matches!(
+            Snapshot::<CellConway>::from_json(&future),
+            Err(SnapshotError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            Snapshot::<CellConway>::from_bytes(&[1, 2, 3]),
+            Err(SnapshotError::UnknownFormat)
+        ));
+        let mut far = Snapshot::of_board(&board);
+        far.neighborhood = NeighborhoodShape::Custom(vec![(1_000_000_000, -1_000_000_000)]);
+        assert!(matches!(
+            Snapshot::<CellConway>::from_json(&far.to_json()),
+            Err(SnapshotError::InvalidNeighborhood {
+                radius: 1_000_000_000
+            })
+        ));
+        far.neighborhood = NeighborhoodShape::Custom(vec![(isize::MIN, 0)]);
+        assert!(matches!(
+            Snapshot::<CellConway>::from_bytes(&far.to_bytes()),
+            Err(SnapshotError::InvalidNeighborhood { .. })
+        ));
+        far.neighborhood = NeighborhoodShape::VonNeumann(2);
+        assert!(Snapshot::<CellConway>::from_json(&far.to_json()).is_ok());
+        let bytes = Snapshot::of_board(&board).to_bytes();
+        assert!(matches!(
+            Snapshot::<CellConway>::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(SnapshotError::Binary(_))
+        ));
+    }
+}
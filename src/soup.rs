@@ -0,0 +1,179 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::{Rng, SeedableRng};
+
+use crate::cell::*;
+use crate::game_board::*;
+
+/// The symmetry of the random cells of a soup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetry {
+    /// Every cell is random
+    #[default]
+    Asymmetric,
+    /// The soup looks the same after a rotation by 180 degrees
+    C2,
+    /// The soup looks the same after a rotation by 90 degrees
+    C4,
+    /// The soup looks the same after any rotation by a multiple of 90 degrees and after mirroring
+    D8,
+}
+
+impl Symmetry {
+    /// Get every position the symmetry maps the position in a region of the given size to, including itself.
+    fn images(&self, x: usize, y: usize, width: usize, height: usize) -> Vec<(usize, usize)> {
+        let (x_mirrored, y_mirrored) = (width - 1 - x, height - 1 - y);
+        match self {
+            Self::Asymmetric => vec![(x, y)],
+            Self::C2 => vec![(x, y), (x_mirrored, y_mirrored)],
+            // Rotations only map a square onto itself, so width and height are equal
+            Self::C4 => vec![
+                (x, y),
+                (y_mirrored, x),
+                (x_mirrored, y_mirrored),
+                (y, x_mirrored),
+            ],
+            Self::D8 => vec![
+                (x, y),
+                (y_mirrored, x),
+                (x_mirrored, y_mirrored),
+                (y, x_mirrored),
+                (x_mirrored, y),
+                (y, x),
+                (x, y_mirrored),
+                (y_mirrored, x_mirrored),
+            ],
+        }
+    }
+}
+
+/// Generates soups, boards of random cells in which each state occurs with its own probability.
+///
+/// The random cells either fill the whole board or a square in its center, like the 16x16 soups of apgsearch.
+/// The cells around the square are filled with a background cell.
+#[derive(Debug, Clone)]
+pub struct Soup<C: Cell> {
+    states: Vec<C>,
+    weights: WeightedIndex<f64>,
+    square: Option<(usize, C)>,
+    symmetry: Symmetry,
+}
+
+impl<C: Cell> Soup<C> {
+    /// Construct a generator of soups filling the whole board, with every state occurring in proportion to its weight.
+    /// Panics if a weight is negative or all of them are zero.
+    pub fn new(states: &[(C, f64)]) -> Self {
+        let weights = WeightedIndex::new(states.iter().map(|&(_, weight)| weight))
+            .expect("The weights of a soup have to be non-negative and cannot all be zero!");
+        Self {
+            states: states.iter().map(|&(state, _)| state).collect(),
+            weights,
+            square: None,
+            symmetry: Symmetry::default(),
+        }
+    }
+    /// Only fill a square of the given size in the center of the board with random cells, and the rest with the background cell.
+    pub fn with_square(mut self, size: usize, background: C) -> Self {
+        self.square = Some((size, background));
+        self
+    }
+    /// Make the random cells symmetric
+    pub fn with_symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+    /// Generate a soup on a board of the given dimension, drawing from the given random number generator.
+    /// Panics if the square does not fit onto the board, or if the symmetry rotates the random cells
+    /// but they do not form a square.
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        width: usize,
+        height: usize,
+        boundary: Boundary<C>,
+        rng: &mut R,
+    ) -> GameBoard<C> {
+        let (x0, y0, size_x, size_y, background) = match self.square {
+            Some((size, background)) => {
+                assert!(
+                    size <= width && size <= height,
+                    "The square of the soup does not fit onto the board!"
+                );
+                (
+                    (width - size) / 2,
+                    (height - size) / 2,
+                    size,
+                    size,
+                    background,
+                )
+            }
+            // Every cell is random, so the background is overwritten anyway
+            None => (0, 0, width, height, self.states[0]),
+        };
+        assert!(
+            size_x == size_y || matches!(self.symmetry, Symmetry::Asymmetric | Symmetry::C2),
+            "Rotational symmetries need a square of random cells!"
+        );
+        let mut board = GameBoard::with_boundary(width, height, background, boundary);
+        // Every cell is either random or a copy of a symmetric cell visited before, which comes first row by row
+        for y in 0..size_y {
+            for x in 0..size_x {
+                let (x_first, y_first) = self
+                    .symmetry
+                    .images(x, y, size_x, size_y)
+                    .into_iter()
+                    .min_by_key(|&(x, y)| (y, x))
+                    .unwrap();
+                let cell = if (x_first, y_first) == (x, y) {
+                    self.states[self.weights.sample(rng)]
+                } else {
+                    *board.get(x0 + x_first, y0 + y_first).unwrap()
+                };
+                *board.get_mut(x0 + x, y0 + y).unwrap() = cell;
+            }
+        }
+        board.update_border();
+        board
+    }
+    /// Generate a soup on a board of the given dimension determined by the seed.
+    /// The same seed always produces the same soup.
+    pub fn generate_seeded(
+        &self,
+        width: usize,
+        height: usize,
+        boundary: Boundary<C>,
+        seed: u64,
+    ) -> GameBoard<C> {
+        self.generate(width, height, boundary, &mut SeedRng::seed_from_u64(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+
+    #[test]
+    fn test_symmetric_square() {
+        let (width, height, size) = (30, 20, 16);
+        let soup = Soup::new(&[(CellConway::Dead, 0.7), (CellConway::Alive, 0.3)])
+            .with_square(size, CellConway::Dead)
+            .with_symmetry(Symmetry::D8);
+        let board = soup.generate_seeded(width, height, Boundary::Fixed(CellConway::Dead), 1);
+        let (x0, y0) = ((width - size) / 2, (height - size) / 2);
+        let soup_cell = |x: usize, y: usize| *board.get(x0 + x, y0 + y).unwrap();
+        let mut alive = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let cell = *board.get(x, y).unwrap();
+                if !(x0..x0 + size).contains(&x) || !(y0..y0 + size).contains(&y) {
+                    assert_eq!(cell, CellConway::Dead);
+                    continue;
+                }
+                let (x, y) = (x - x0, y - y0);
+                assert_eq!(cell, soup_cell(size - 1 - y, x));
+                assert_eq!(cell, soup_cell(size - 1 - x, y));
+                alive += (cell == CellConway::Alive) as usize;
+            }
+        }
+        assert!(alive > 0 && alive < size * size / 2);
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::cell::*;
+use crate::game::traits;
+use crate::game_board::*;
+use crate::game_rules::*;
+
+/// Width and height of the chunks a SparseBoard is made of.
+pub const CHUNK_SIZE: usize = 32;
+
+type ChunkCoord = (i64, i64);
+
+/// An unbounded board which only stores the chunks containing cells different from the background cell.
+#[derive(Debug, Clone)]
+pub struct SparseBoard<C: Cell> {
+    background: C,
+    chunks: HashMap<ChunkCoord, Vec<C>>,
+}
+
+impl<C: Cell> SparseBoard<C> {
+    /// Construct an empty SparseBoard where every cell is the background cell.
+    pub fn new(background: C) -> Self {
+        Self {
+            background,
+            chunks: HashMap::new(),
+        }
+    }
+    /// Get the cell every position not covered by a chunk holds
+    pub fn background(&self) -> C {
+        self.background
+    }
+    /// Get the cell at the given coordinate
+    pub fn get(&self, x: i64, y: i64) -> C {
+        let (chunk, i) = chunk_index(x, y);
+        self.chunks
+            .get(&chunk)
+            .map_or(self.background, |chunk| chunk[i])
+    }
+    /// Set the cell at the given coordinate, allocating its chunk if necessary
+    pub fn set(&mut self, x: i64, y: i64, cell: C) {
+        let (chunk, i) = chunk_index(x, y);
+        if cell == self.background && !self.chunks.contains_key(&chunk) {
+            return;
+        }
+        let background = self.background;
+        self.chunks
+            .entry(chunk)
+            .or_insert_with(|| vec![background; CHUNK_SIZE * CHUNK_SIZE])[i] = cell;
+    }
+    /// Reset every cell to the given background cell and free all chunks
+    pub fn clear(&mut self, background: C) {
+        self.background = background;
+        self.chunks.clear();
+    }
+    /// Get the number of allocated chunks
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+    /// Get the region covered by the allocated chunks as (min_x, min_y, max_x, max_y), with the maximum excluded.
+    /// Returns None if every cell is the background cell.
+    pub fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        let size = CHUNK_SIZE as i64;
+        self.chunks.keys().fold(None, |bounds, &(cx, cy)| {
+            let (x0, y0, x1, y1) = (cx * size, cy * size, (cx + 1) * size, (cy + 1) * size);
+            Some(match bounds {
+                None => (x0, y0, x1, y1),
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x0), min_y.min(y0), max_x.max(x1), max_y.max(y1))
+                }
+            })
+        })
+    }
+    /// Apply the rule to every chunk and to the chunks the pattern is about to grow into.
+    /// The scratch board has to be a single chunk with the neighborhood of the rule.
+    /// Chunks which end up containing only background cells are freed.
+    fn step<R: GameRule<Cell = C>>(&mut self, rule: &R, scratch_board: &mut GameBoard<C>) {
+        let radius = scratch_board.neighborhood().radius();
+        let mut to_compute = HashSet::with_capacity(self.chunks.len());
+        for (&(cx, cy), chunk) in &self.chunks {
+            to_compute.insert((cx, cy));
+            // Check which edges of the chunk have cells close enough to affect the neighboring chunks
+            let (mut left, mut right, mut top, mut bottom) = (false, false, false, false);
+            for (i, cell) in chunk.iter().enumerate() {
+                if *cell != self.background {
+                    let (x, y) = (i % CHUNK_SIZE, i / CHUNK_SIZE);
+                    left |= x < radius;
+                    right |= x + radius >= CHUNK_SIZE;
+                    top |= y < radius;
+                    bottom |= y + radius >= CHUNK_SIZE;
+                }
+            }
+            for (dy, reach_y) in [(-1, top), (0, true), (1, bottom)] {
+                for (dx, reach_x) in [(-1, left), (0, true), (1, right)] {
+                    if reach_x && reach_y {
+                        to_compute.insert((cx + dx, cy + dy));
+                    }
+                }
+            }
+        }
+
+        let mut chunks = HashMap::with_capacity(to_compute.len());
+        let size = CHUNK_SIZE as isize;
+        for (cx, cy) in to_compute {
+            // The border of the scratch board only reaches into the directly surrounding chunks
+            let around: Vec<Option<&Vec<C>>> = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (cx + dx, cy + dy)))
+                .map(|chunk| self.chunks.get(&chunk))
+                .collect();
+            scratch_board.fill_outer(|x, y| {
+                let (dx, dy) = (x.div_euclid(size), y.div_euclid(size));
+                let i = (y.rem_euclid(size) * size + x.rem_euclid(size)) as usize;
+                around[((dy + 1) * 3 + dx + 1) as usize].map_or(self.background, |chunk| chunk[i])
+            });
+            let chunk: Vec<C> = rule
+                .iter(scratch_board)
+                .take(CHUNK_SIZE * CHUNK_SIZE)
+                .collect();
+            if chunk.iter().any(|cell| *cell != self.background) {
+                chunks.insert((cx, cy), chunk);
+            }
+        }
+        self.chunks = chunks;
+    }
+}
+
+/// A game on an unbounded SparseBoard. Only a rectangular viewport of it is visible through `get_board`.
+///
+/// The rule has to keep a neighborhood made up of background cells unchanged,
+/// otherwise the board would have to grow infinitely in a single step.
+/// Like the rules of a CellGame, the rule has to be Send and Sync to be played.
+pub struct SparseGame<C: Cell, R: GameRule<Cell = C>> {
+    rule: R,
+    board: SparseBoard<C>,
+    scratch_board: GameBoard<C>,
+    view: GameBoard<C>,
+    origin: (i64, i64),
+    generation: u64,
+}
+
+impl<C: Cell, R: GameRule<Cell = C> + Send + Sync> SparseGame<C, R> {
+    /// Construct a SparseGame with the cells of the GameBoard at the origin and a viewport of the same size.
+    pub fn from_board(board: &GameBoard<C>, background: C) -> Self {
+        let (width, height) = board.dim();
+        let mut game = <Self as traits::CellGame>::new(width, height, background);
+        for (i, cell) in board.iter().enumerate() {
+            game.board
+                .set((i % width) as i64, (i / width) as i64, *cell);
+        }
+        game
+    }
+    /// Get the unbounded board
+    pub fn sparse_board(&self) -> &SparseBoard<C> {
+        &self.board
+    }
+    /// Get the unbounded board mutably, for example to place patterns outside of the viewport
+    pub fn sparse_board_mut(&mut self) -> &mut SparseBoard<C> {
+        &mut self.board
+    }
+    /// Get the coordinate of the top left cell of the viewport on the unbounded board
+    pub fn origin(&self) -> (i64, i64) {
+        self.origin
+    }
+    /// Move the viewport so its top left cell is at the given coordinate of the unbounded board
+    pub fn set_origin(&mut self, origin: (i64, i64)) {
+        self.origin = origin;
+    }
+}
+
+impl<C: Cell, R: GameRule<Cell = C> + Send + Sync> traits::CellGame for SparseGame<C, R> {
+    type Cell = C;
+
+    type GameRule = R;
+
+    fn get_board(&self) -> &GameBoard<Self::Cell> {
+        &self.view
+    }
+    fn update_board(&mut self) {
+        let (width, _) = self.view.dim();
+        let (x0, y0) = self.origin;
+        for (i, cell) in self.view.iter_mut().enumerate() {
+            *cell = self
+                .board
+                .get(x0 + (i % width) as i64, y0 + (i / width) as i64);
+        }
+        self.view.update_border();
+    }
+    fn step(&mut self) {
+        self.board.step(&self.rule, &mut self.scratch_board);
+        self.generation += 1;
+    }
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+    fn clear(&mut self, clear_cell: Self::Cell) {
+        self.board.clear(clear_cell);
+    }
+    fn dim(&self) -> (usize, usize) {
+        self.view.dim()
+    }
+    fn next_cell(&mut self, x: usize, y: usize) {
+        let (x0, y0) = self.origin;
+        let (x_sparse, y_sparse) = (x0 + x as i64, y0 + y as i64);
+        let next_cell = self.board.get(x_sparse, y_sparse).next();
+        self.board.set(x_sparse, y_sparse, next_cell);
+        self.view.set(x, y, next_cell);
+    }
+    /// Construct an empty SparseGame with the given cell as background and a viewport of the given size.
+    /// The board is unbounded, so the boundary only lines the viewport.
+    fn with_boundary(
+        width: usize,
+        height: usize,
+        cell: Self::Cell,
+        boundary: Boundary<Self::Cell>,
+    ) -> Self {
+        let neighborhood = R::neighborhood();
+        assert!(
+            neighborhood.radius() <= CHUNK_SIZE,
+            "The neighborhood cannot reach further than one chunk!"
+        );
+        let mut scratch_board = GameBoard::new(CHUNK_SIZE, CHUNK_SIZE, cell);
+        scratch_board.set_neighborhood(neighborhood);
+        Self {
+            rule: R::default(),
+            board: SparseBoard::new(cell),
+            scratch_board,
+            view: GameBoard::with_boundary(width, height, cell, boundary),
+            origin: (0, 0),
+            generation: 0,
+        }
+    }
+}
+
+/// Get the chunk containing the coordinate and the index of the coordinate within that chunk
+fn chunk_index(x: i64, y: i64) -> (ChunkCoord, usize) {
+    let size = CHUNK_SIZE as i64;
+    let chunk = (x.div_euclid(size), y.div_euclid(size));
+    let i = (y.rem_euclid(size) * size + x.rem_euclid(size)) as usize;
+    (chunk, i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+    use crate::game::{traits::CellGame as _, CellGame};
+
+    #[test]
+    fn test_glider_leaves_viewport() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let (width, height) = (80, 80);
+        let mut dense = CellGame::<CellConway, ConwayRule>::new(width, height, CellConway::Dead);
+        let mut sparse = SparseGame::<CellConway, ConwayRule>::new(8, 8, CellConway::Dead);
+        for &(x, y) in &glider {
+            dense.next_cell(x, y);
+            sparse.next_cell(x, y);
+        }
+        for _ in 0..200 {
+            dense.step();
+            sparse.step();
+        }
+        // The glider moved 50 cells diagonally, far outside of the viewport and across chunks
+        for (i, cell) in dense.get_board().iter().enumerate() {
+            let (x, y) = ((i % width) as i64, (i / width) as i64);
+            assert_eq!(*cell, sparse.sparse_board().get(x, y));
+        }
+        assert!(sparse.sparse_board().num_chunks() <= 4);
+        sparse.update_board();
+        assert!(sparse.get_board().iter().all(|&c| c == CellConway::Dead));
+    }
+}
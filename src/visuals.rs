@@ -2,22 +2,41 @@ use std::convert::TryInto;
 
 use crate::cell::Cell;
 use crate::game::traits;
+use crate::game_board::{GameBoard, Topology};
 use crate::globals::*;
 use pixels::Pixels;
 use winit::window::Window;
+
+/// Width and height of a hexagonal cell in pixels. Every row is shifted by half a cell.
+const HEX_CELL_SIZE: usize = 2;
+
 pub struct Visuals {
     pixel_buffer: Pixels,
     window: Window,
     decay_multiplier: f32, // 1.0 -> instant decay, 0.0 -> never decay
+    topology: Topology,
+    board_dim: (usize, usize),
+    // Whether the whole pixel buffer has to be redrawn, even if the game reports which tiles changed
+    full_redraw: bool,
 }
 
 impl Visuals {
     pub fn new(width: usize, height: usize, window: Window) -> Visuals {
+        Self::with_topology(width, height, Topology::Square, window)
+    }
+    /// Construct Visuals for a board of the given dimension whose cells are arranged according to the topology.
+    pub fn with_topology(
+        width: usize,
+        height: usize,
+        topology: Topology,
+        window: Window,
+    ) -> Visuals {
         let win_size = window.inner_size();
         let surface = pixels::SurfaceTexture::new(win_size.width, win_size.height, &window);
+        let (buffer_width, buffer_height) = buffer_size(width, height, topology);
         let pixel_buffer: Pixels = pixels::Pixels::new(
-            width.try_into().expect(OVERFLOW_MSG),
-            height.try_into().expect(OVERFLOW_MSG),
+            buffer_width.try_into().expect(OVERFLOW_MSG),
+            buffer_height.try_into().expect(OVERFLOW_MSG),
             surface,
         )
         .expect("Cannot create pixel texture!");
@@ -27,6 +46,9 @@ impl Visuals {
             pixel_buffer,
             window,
             decay_multiplier,
+            topology,
+            board_dim: (width, height),
+            full_redraw: true,
         }
     }
     pub fn update_pixel_buffer<T: traits::CellGame>(
@@ -34,28 +56,55 @@ impl Visuals {
         game: &T,
         overwrite_decaying: fn(&T::Cell) -> bool,
     ) {
-        for (pixel, c) in self
-            .pixel_buffer
-            .get_frame()
-            .chunks_exact_mut(4)
-            .zip(game.get_board().into_iter())
-        {
-            let rgba = c.to_rgba();
-            if overwrite_decaying(c) {
-                pixel.copy_from_slice(&rgba.get_raw());
-            } else {
-                let decay_multiplier = self.decay_multiplier;
-                pixel
-                    .iter_mut()
-                    .zip(rgba.get_raw().iter())
-                    .for_each(|(byte, new_rgba)| {
-                        *byte = (*byte as f32 * (1.0 - decay_multiplier)
-                            + *new_rgba as f32 * decay_multiplier)
-                            as u8
-                    })
+        let decay_multiplier = self.decay_multiplier;
+        match self.topology {
+            // Unchanged cells keep their pixels only if the colors do not fade
+            Topology::Square if decay_multiplier == 1.0 && !self.full_redraw => {
+                match game.dirty_tiles() {
+                    Some(tiles) => {
+                        let (width, _) = self.board_dim;
+                        let board = game.get_board();
+                        let frame = self.pixel_buffer.get_frame();
+                        for (tx, ty) in tiles.iter() {
+                            let (xs, ys) = tiles.cells(tx, ty);
+                            for y in ys {
+                                let row = &mut frame
+                                    [(y * width + xs.start) * 4..(y * width + xs.end) * 4];
+                                for (pixel, x) in row.chunks_exact_mut(4).zip(xs.clone()) {
+                                    let c =
+                                        board.get(x, y).expect("Dirty tile outside of the board!");
+                                    blend_pixel(pixel, c, overwrite_decaying(c), decay_multiplier);
+                                }
+                            }
+                        }
+                    }
+                    None => self.update_all_pixels(game, overwrite_decaying),
+                }
             }
+            Topology::Square => {
+                self.update_all_pixels(game, overwrite_decaying);
+                self.full_redraw = false;
+            }
+            Topology::Hexagonal => self.update_all_pixels(game, overwrite_decaying),
         }
     }
+    fn update_all_pixels<T: traits::CellGame>(
+        &mut self,
+        game: &T,
+        overwrite_decaying: fn(&T::Cell) -> bool,
+    ) {
+        draw_board(
+            self.pixel_buffer.get_frame(),
+            game.get_board(),
+            overwrite_decaying,
+            self.decay_multiplier,
+        );
+    }
+    /// Map a position in the pixel buffer to the coordinates of the cell drawn there.
+    /// Returns None if no cell is drawn at this position.
+    pub fn pixel_to_cell(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        pixel_to_cell(x, y, self.board_dim, self.topology)
+    }
     pub fn render(&mut self) -> Result<(), pixels::Error> {
         self.pixel_buffer.render()
     }
@@ -67,6 +116,7 @@ impl Visuals {
             return Err("Out of bounds!");
         }
         self.decay_multiplier = decay_multiplier;
+        self.full_redraw = true;
         Ok(())
     }
     pub fn get_window(&self) -> &Window {
@@ -79,6 +129,131 @@ impl Visuals {
         self.pixel_buffer.resize_surface(width, height)
     }
     pub fn resize_buffer(&mut self, width: u32, height: u32) {
-        self.pixel_buffer.resize_buffer(width, height)
+        self.pixel_buffer.resize_buffer(width, height);
+        self.full_redraw = true;
+    }
+}
+
+/// Size of the pixel buffer needed to draw a board of the given dimension and topology
+pub(crate) fn buffer_size(width: usize, height: usize, topology: Topology) -> (usize, usize) {
+    match topology {
+        Topology::Square => (width, height),
+        Topology::Hexagonal => (
+            width * HEX_CELL_SIZE + height.saturating_sub(1) * HEX_CELL_SIZE / 2,
+            height * HEX_CELL_SIZE,
+        ),
+    }
+}
+
+/// Map a position in a pixel buffer of the size given by `buffer_size` to the coordinates of the cell drawn there.
+/// Returns None for the gaps hexagonal boards leave at the start and end of the shifted rows.
+pub(crate) fn pixel_to_cell(
+    x: usize,
+    y: usize,
+    (width, height): (usize, usize),
+    topology: Topology,
+) -> Option<(usize, usize)> {
+    let (x, y) = match topology {
+        Topology::Square => (x, y),
+        Topology::Hexagonal => {
+            let r = y / HEX_CELL_SIZE;
+            let q = x.checked_sub(r * HEX_CELL_SIZE / 2)? / HEX_CELL_SIZE;
+            (q, r)
+        }
+    };
+    (x < width && y < height).then_some((x, y))
+}
+
+/// Draw every cell of the board into the frame of RGBA pixels, which has the size given by `buffer_size`.
+/// Cells for which `overwrite_decaying` is false fade in over the previous colors according to the decay multiplier.
+pub(crate) fn draw_board<C: Cell>(
+    frame: &mut [u8],
+    board: &GameBoard<C>,
+    overwrite_decaying: fn(&C) -> bool,
+    decay_multiplier: f32,
+) {
+    match board.topology() {
+        Topology::Square => {
+            for (pixel, c) in frame.chunks_exact_mut(4).zip(board) {
+                blend_pixel(pixel, c, overwrite_decaying(c), decay_multiplier);
+            }
+        }
+        Topology::Hexagonal => {
+            let (width, height) = board.dim();
+            let (buffer_width, _) = buffer_size(width, height, Topology::Hexagonal);
+            for (i, c) in board.iter().enumerate() {
+                let (q, r) = (i % width, i / width);
+                let overwrite = overwrite_decaying(c);
+                // Each cell is a HEX_CELL_SIZE square of pixels, shifted by half a cell per row
+                let (x0, y0) = (q * HEX_CELL_SIZE + r * HEX_CELL_SIZE / 2, r * HEX_CELL_SIZE);
+                for y in y0..y0 + HEX_CELL_SIZE {
+                    let row_start = (y * buffer_width + x0) * 4;
+                    frame[row_start..row_start + HEX_CELL_SIZE * 4]
+                        .chunks_exact_mut(4)
+                        .for_each(|pixel| blend_pixel(pixel, c, overwrite, decay_multiplier));
+                }
+            }
+        }
+    }
+}
+
+/// Write the color of the cell into the pixel, either directly or blended with the previous color
+fn blend_pixel<C: Cell>(pixel: &mut [u8], cell: &C, overwrite: bool, decay_multiplier: f32) {
+    let rgba = cell.to_rgba();
+    if overwrite {
+        pixel.copy_from_slice(&rgba.get_raw());
+    } else {
+        pixel
+            .iter_mut()
+            .zip(rgba.get_raw().iter())
+            .for_each(|(byte, new_rgba)| {
+                *byte = (*byte as f32 * (1.0 - decay_multiplier)
+                    + *new_rgba as f32 * decay_multiplier) as u8
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::mock::*;
+    use crate::game_board::NeighborhoodShape;
+
+    #[test]
+    fn test_hex_pixels_map_to_cells() {
+        let (width, height) = (4, 3);
+        let mut board = GameBoard::new(width, height, CellConway::Dead);
+        board.set_neighborhood(NeighborhoodShape::Hexagonal);
+        for (q, r) in [(0, 0), (3, 0), (1, 1), (0, 2), (3, 2)] {
+            board.set(q, r, CellConway::Alive);
+        }
+        let dim = buffer_size(width, height, Topology::Hexagonal);
+        assert_eq!(dim, (10, 6));
+        // Pixels which are not drawn keep their transparent black
+        let mut frame = vec![0; dim.0 * dim.1 * 4];
+        draw_board(&mut frame, &board, |_| true, 1.0);
+        for (i, pixel) in frame.chunks_exact(4).enumerate() {
+            let (x, y) = (i % dim.0, i / dim.0);
+            match pixel_to_cell(x, y, (width, height), Topology::Hexagonal) {
+                Some((q, r)) => {
+                    assert_eq!(pixel, board.get(q, r).unwrap().to_rgba().get_raw());
+                }
+                None => assert_eq!(pixel, [0; 4], "pixel ({}, {}) is drawn", x, y),
+            }
+        }
+        // Row 1 is shifted by one pixel, leaving a gap at its start and after its end
+        let hex = |x, y| pixel_to_cell(x, y, (width, height), Topology::Hexagonal);
+        assert_eq!(hex(0, 2), None);
+        assert_eq!(
+            (hex(1, 2), hex(2, 3), hex(8, 2)),
+            (Some((0, 1)), Some((0, 1)), Some((3, 1)))
+        );
+        assert_eq!(hex(9, 3), None);
+        assert_eq!(
+            (hex(1, 5), hex(2, 4), hex(9, 4)),
+            (None, Some((0, 2)), Some((3, 2)))
+        );
+        assert_eq!((hex(8, 0), hex(9, 1)), (None, None));
+        assert_eq!(hex(0, 6), None);
     }
 }